@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DataEnum, DeriveInput, Fields, Ident, Type, Variant};
 
 #[proc_macro_derive(FromBytes)]
 pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
@@ -16,3 +17,268 @@ pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
     };
     TokenStream::from(expanded)
 }
+
+/// Derives `ollama_sdk::parser::StreamEventExt` for an event enum, and a matching stream
+/// type that parses a response body into the events.
+///
+/// Variants are marked with `#[stream(...)]`:
+///
+/// - `#[stream(message)]` on a variant with a single field, the message type of a line.
+/// - `#[stream(error)]` on a variant with a single `String` field, for server errors.
+/// - `#[stream(partial)]` on a variant with the fields `partial: String` and
+///   `error: Option<String>`, or two unnamed fields of these types, for un-parseable lines.
+/// - `#[stream(line_error)]`, optionally, on a variant with a single
+///   `ollama_sdk::parser::StreamLineError` field, which then receives server errors and
+///   un-parseable lines with their position instead of the two variants above.
+///
+/// The stream type is named after the enum, with a trailing `Event` replaced by `Stream`
+/// (`FooStreamEvent` and `FooEvent` give `FooStream`), and has the visibility of the
+/// enum. Add `#[stream(no_wrapper)]` to the enum to only derive the trait.
+#[proc_macro_derive(StreamEvent, attributes(stream))]
+pub fn derive_stream_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_stream_event(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The variants of an event enum marked with `#[stream(...)]`.
+#[derive(Default)]
+struct StreamVariants<'a> {
+    message: Option<(&'a Variant, &'a Type)>,
+    error: Option<&'a Variant>,
+    partial: Option<&'a Variant>,
+    line_error: Option<&'a Variant>,
+}
+
+fn expand_stream_event(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "StreamEvent can only be derived for enums",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "StreamEvent cannot be derived for generic enums",
+        ));
+    }
+
+    let mut wrapper = true;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("stream"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("no_wrapper") {
+                wrapper = false;
+                Ok(())
+            } else {
+                Err(meta.error("expected `no_wrapper`"))
+            }
+        })?;
+    }
+
+    let variants = stream_variants(data)?;
+    let missing = |kind: &str| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("StreamEvent requires a variant marked with `#[stream({kind})]`"),
+        )
+    };
+    let (message, message_type) = variants.message.ok_or_else(|| missing("message"))?;
+    let error = variants.error.ok_or_else(|| missing("error"))?;
+    let partial = variants.partial.ok_or_else(|| missing("partial"))?;
+
+    let message = &message.ident;
+    let error = &error.ident;
+    let partial_ident = &partial.ident;
+    let build_partial = match &partial.fields {
+        Fields::Named(_) => quote!(#name::#partial_ident { partial, error }),
+        _ => quote!(#name::#partial_ident(partial, error)),
+    };
+    let from_line_error = variants.line_error.map(|variant| {
+        let ident = &variant.ident;
+        quote! {
+            fn from_line_error(error: ::ollama_sdk::parser::StreamLineError) -> Self {
+                #name::#ident(error)
+            }
+        }
+    });
+
+    let mut expanded = quote! {
+        impl ::ollama_sdk::parser::StreamEventExt<#message_type> for #name {
+            fn from_message(msg: #message_type) -> Self {
+                #name::#message(msg)
+            }
+
+            fn from_error(err: ::std::string::String) -> Self {
+                #name::#error(err)
+            }
+
+            fn partial(
+                partial: ::std::string::String,
+                error: ::std::option::Option<::std::string::String>,
+            ) -> Self {
+                #build_partial
+            }
+
+            #from_line_error
+        }
+    };
+    if wrapper {
+        expanded.extend(expand_wrapper(&input, message_type));
+    }
+    Ok(expanded)
+}
+
+fn stream_variants(data: &DataEnum) -> syn::Result<StreamVariants<'_>> {
+    let mut variants = StreamVariants::default();
+    for variant in &data.variants {
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("stream"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("message") {
+                    let ty = single_field(variant, "message")?;
+                    if variants.message.replace((variant, ty)).is_some() {
+                        return Err(meta.error("duplicate `#[stream(message)]` variant"));
+                    }
+                    return Ok(());
+                } else if meta.path.is_ident("error") {
+                    single_field(variant, "error")?;
+                    &mut variants.error
+                } else if meta.path.is_ident("partial") {
+                    partial_fields(variant)?;
+                    &mut variants.partial
+                } else if meta.path.is_ident("line_error") {
+                    single_field(variant, "line_error")?;
+                    &mut variants.line_error
+                } else {
+                    return Err(
+                        meta.error("expected `message`, `error`, `partial` or `line_error`")
+                    );
+                };
+                if slot.replace(variant).is_some() {
+                    return Err(meta.error("duplicate variant for this kind of event"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(variants)
+}
+
+/// Returns the type of the only unnamed field of `variant`.
+fn single_field<'a>(variant: &'a Variant, kind: &str) -> syn::Result<&'a Type> {
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(&fields.unnamed[0].ty),
+        _ => Err(syn::Error::new_spanned(
+            variant,
+            format!("a `#[stream({kind})]` variant must have exactly one unnamed field"),
+        )),
+    }
+}
+
+/// Checks that `variant` can hold the text and error of an un-parseable line.
+fn partial_fields(variant: &Variant) -> syn::Result<()> {
+    let valid = match &variant.fields {
+        Fields::Named(fields) => {
+            let mut names: Vec<_> = fields
+                .named
+                .iter()
+                .filter_map(|field| field.ident.as_ref().map(Ident::to_string))
+                .collect();
+            names.sort();
+            names == ["error", "partial"]
+        }
+        Fields::Unnamed(fields) => fields.unnamed.len() == 2,
+        Fields::Unit => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            variant,
+            "a `#[stream(partial)]` variant must have the fields `partial` and `error`, \
+             or two unnamed fields",
+        ))
+    }
+}
+
+/// Generates the stream type for the event enum of `input`.
+fn expand_wrapper(input: &DeriveInput, message_type: &Type) -> proc_macro2::TokenStream {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let base = name.to_string();
+    let base = base
+        .strip_suffix("StreamEvent")
+        .or_else(|| base.strip_suffix("Event"))
+        .unwrap_or(&base);
+    let stream = format_ident!("{}Stream", base);
+    let event_doc = format!("A stream of [`{name}`]s parsed from a streaming response body.");
+
+    quote! {
+        #[doc = #event_doc]
+        #vis struct #stream {
+            /// The parsed events.
+            pub inner: ::std::pin::Pin<
+                ::std::boxed::Box<
+                    dyn ::ollama_sdk::__private::Stream<Item = ::ollama_sdk::Result<#name>>
+                        + ::std::marker::Send,
+                >,
+            >,
+        }
+
+        impl #stream {
+            /// Creates a stream of events from the chunks of a response body.
+            #vis fn from_bytes_stream<S>(stream: S) -> Self
+            where
+                S: ::ollama_sdk::__private::Stream<
+                        Item = ::ollama_sdk::Result<::ollama_sdk::__private::Bytes>,
+                    > + ::std::marker::Send
+                    + ::std::marker::Unpin
+                    + 'static,
+            {
+                Self::from_bytes_stream_with_config(stream, ::ollama_sdk::parser::ParserConfig::default())
+            }
+
+            /// Creates a stream of events from the chunks of a response body, parsed with
+            /// the framing and limits of `config`.
+            #vis fn from_bytes_stream_with_config<S>(
+                stream: S,
+                config: ::ollama_sdk::parser::ParserConfig,
+            ) -> Self
+            where
+                S: ::ollama_sdk::__private::Stream<
+                        Item = ::ollama_sdk::Result<::ollama_sdk::__private::Bytes>,
+                    > + ::std::marker::Send
+                    + ::std::marker::Unpin
+                    + 'static,
+            {
+                let parser = ::ollama_sdk::parser::GenericStreamParser::<S, #message_type, #name>::with_config(
+                    stream, config,
+                );
+                Self {
+                    inner: ::std::boxed::Box::pin(parser),
+                }
+            }
+        }
+
+        impl ::ollama_sdk::__private::Stream for #stream {
+            type Item = ::ollama_sdk::Result<#name>;
+
+            fn poll_next(
+                mut self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<::std::option::Option<Self::Item>> {
+                self.inner.as_mut().poll_next(cx)
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Measures the cost of turning a large chat request into request body bytes.
+//!
+//! `json_value_then_bytes` reproduces the previous behaviour, where the request was first
+//! converted to a `serde_json::Value` and the transport serialized that value again.
+//! `http_request_body` is the current [`HttpRequest::body`], which serializes once.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde::Serialize;
+use std::hint::black_box;
+
+use ollama_sdk::types::{HttpRequest, Role};
+
+const MESSAGES: usize = 200;
+const IMAGE_BYTES: usize = 16 * 1024;
+
+/// Returns a deterministic base64-looking string of `len` characters.
+fn fake_image(seed: usize, len: usize) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    (0..len)
+        .map(|i| ALPHABET[(i * 31 + seed * 7) % ALPHABET.len()] as char)
+        .collect()
+}
+
+/// A chat message in the shape Ollama expects for multimodal models.
+#[derive(Serialize)]
+struct ImageMessage {
+    role: Role,
+    content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+}
+
+/// A chat request body with a history of [`ImageMessage`]s.
+#[derive(Serialize)]
+struct ImageChatRequest {
+    model: String,
+    messages: Vec<ImageMessage>,
+    stream: bool,
+}
+
+fn chat_history() -> ImageChatRequest {
+    let messages = (0..MESSAGES)
+        .map(|i| ImageMessage {
+            role: if i % 2 == 0 {
+                Role::User
+            } else {
+                Role::Assistant
+            },
+            content: format!("Message {} describing the picture", i),
+            images: if i % 2 == 0 {
+                vec![fake_image(i, IMAGE_BYTES)]
+            } else {
+                Vec::new()
+            },
+        })
+        .collect();
+    ImageChatRequest {
+        model: "llava".to_string(),
+        messages,
+        stream: true,
+    }
+}
+
+fn request_body(c: &mut Criterion) {
+    let chat_request = chat_history();
+    let body_len = serde_json::to_vec(&chat_request).unwrap().len();
+
+    let mut group = c.benchmark_group("chat_request_body");
+    group.throughput(Throughput::Bytes(body_len as u64));
+    group.bench_function("json_value_then_bytes", |b| {
+        b.iter(|| {
+            let value = serde_json::to_value(black_box(&chat_request)).unwrap();
+            black_box(serde_json::to_vec(&value).unwrap())
+        })
+    });
+    group.bench_function("http_request_body", |b| {
+        b.iter(|| {
+            black_box(
+                HttpRequest::new("/api/chat")
+                    .post()
+                    .body(black_box(&chat_request))
+                    .unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, request_body);
+criterion_main!(benches);
@@ -0,0 +1,150 @@
+//! Measures NDJSON stream parsing for long chat streams made of tiny token chunks.
+//!
+//! Each line is a chat response carrying a single short token, as Ollama emits while
+//! generating. The same stream is fed to the parser one line per chunk, and split into
+//! small fixed-size chunks that cut lines at arbitrary points.
+//!
+//! `current` is [`GenericStreamParser`]. `previous` reproduces the parser it replaced,
+//! which scanned a `Vec<u8>` with `iter().position`, drained every line into a new
+//! vector and ran `from_utf8_lossy` and `trim` on it before deserializing.
+//!
+//! `line_deserialization` compares the two ways of telling a message line from an error
+//! line: deserializing into the message and parsing an error object only when that
+//! fails, as [`GenericStreamParser`] does, and deserializing once into an untagged
+//! message-or-error enum.
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::{executor, stream, StreamExt};
+use std::hint::black_box;
+
+use ollama_sdk::parser::GenericStreamParser;
+use ollama_sdk::types::chat::{ChatResponse, ChatStreamEvent};
+use ollama_sdk::types::OllamaError;
+use ollama_sdk::Result;
+use serde::Deserialize;
+
+const LINES: usize = 10_000;
+
+fn ndjson_lines() -> Vec<Bytes> {
+    (0..LINES)
+        .map(|i| {
+            Bytes::from(format!(
+                "{{\"model\":\"llama3\",\"created_at\":\"2024-01-01T00:00:00Z\",\
+                 \"message\":{{\"role\":\"assistant\",\"content\":\"tok{} \"}},\"done\":{}}}\n",
+                i,
+                i + 1 == LINES
+            ))
+        })
+        .collect()
+}
+
+fn rechunk(lines: &[Bytes], size: usize) -> Vec<Bytes> {
+    let all: Vec<u8> = lines.iter().flat_map(|line| line.iter().copied()).collect();
+    all.chunks(size).map(Bytes::copy_from_slice).collect()
+}
+
+fn parse(chunks: &[Bytes]) -> usize {
+    let stream = stream::iter(chunks.iter().cloned().map(Ok::<_, ollama_sdk::Error>));
+    let parser = GenericStreamParser::<_, ChatResponse, ChatStreamEvent>::new(stream);
+    executor::block_on(
+        parser
+            .filter(|event: &Result<ChatStreamEvent>| {
+                futures::future::ready(matches!(event, Ok(ChatStreamEvent::Message(_))))
+            })
+            .count(),
+    )
+}
+
+/// Counts the messages in `chunks` the way the previous line parser found them.
+fn parse_previous(chunks: &[Bytes]) -> usize {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut messages = 0;
+    for chunk in chunks {
+        buffer.extend_from_slice(chunk);
+        while let Some(newline_pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line_bytes = buffer.drain(..=newline_pos).collect::<Vec<u8>>();
+            let line_str = String::from_utf8_lossy(&line_bytes);
+            let line_str = line_str.trim();
+            if line_str.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ChatResponse>(line_str) {
+                Ok(msg) => {
+                    black_box(msg);
+                    messages += 1;
+                }
+                Err(_) => {
+                    black_box(serde_json::from_str::<OllamaError>(line_str).ok());
+                }
+            }
+        }
+    }
+    messages
+}
+
+/// A line deserialized in a single pass, as either a message or an error object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Line {
+    Message(ChatResponse),
+    Error(OllamaError),
+}
+
+/// Deserializes `line` as a message, then as an error object if it is not a message.
+fn two_pass(line: &[u8]) -> bool {
+    match serde_json::from_slice::<ChatResponse>(line) {
+        Ok(msg) => black_box(msg).done,
+        Err(_) => black_box(serde_json::from_slice::<OllamaError>(line).is_ok()),
+    }
+}
+
+/// Deserializes `line` once into the untagged [`Line`].
+fn single_pass(line: &[u8]) -> bool {
+    match serde_json::from_slice::<Line>(line) {
+        Ok(Line::Message(msg)) => black_box(msg).done,
+        Ok(Line::Error(error)) => black_box(!error.error.is_empty()),
+        Err(_) => false,
+    }
+}
+
+fn line_deserialization(c: &mut Criterion) {
+    let message = ndjson_lines().pop().unwrap();
+    let error = Bytes::from_static(br#"{"error":"model 'llama3' not found"}"#);
+
+    let mut group = c.benchmark_group("line_deserialization");
+    for (name, line) in [("message", &message), ("error", &error)] {
+        group.bench_with_input(BenchmarkId::new("two_pass", name), line, |b, line| {
+            b.iter(|| two_pass(black_box(line)))
+        });
+        group.bench_with_input(BenchmarkId::new("untagged_enum", name), line, |b, line| {
+            b.iter(|| single_pass(black_box(line)))
+        });
+    }
+    group.finish();
+}
+
+fn stream_parser(c: &mut Criterion) {
+    let lines = ndjson_lines();
+    let total: usize = lines.iter().map(Bytes::len).sum();
+
+    let mut inputs = vec![("line_per_chunk".to_string(), lines.clone())];
+    for size in [4, 16, 64] {
+        inputs.push((format!("fixed_chunks_{}", size), rechunk(&lines, size)));
+    }
+
+    let mut group = c.benchmark_group("ndjson_chat_stream");
+    group.throughput(Throughput::Bytes(total as u64));
+    for (name, chunks) in &inputs {
+        group.bench_with_input(BenchmarkId::new("current", name), chunks, |b, chunks| {
+            b.iter(|| assert_eq!(parse(black_box(chunks)), LINES))
+        });
+        group.bench_with_input(BenchmarkId::new("previous", name), chunks, |b, chunks| {
+            b.iter(|| assert_eq!(parse_previous(black_box(chunks)), LINES))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, stream_parser, line_deserialization);
+criterion_main!(benches);
@@ -24,7 +24,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match event {
             Ok(val) => match val {
                 ChatStreamEvent::Message(response) => print!("{}", response.message.content),
-                ChatStreamEvent::Error(error) => println!("\nError Chunk: {}", error),
+                ChatStreamEvent::LineError(error) => println!("\nError Chunk: {}", error),
                 _ => continue,
             },
             Err(e) => eprintln!("Chat Error: {}", e),
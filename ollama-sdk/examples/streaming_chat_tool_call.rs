@@ -13,11 +13,13 @@ use serde_json::Value;
 use tokio_util::sync::CancellationToken;
 
 use ollama_sdk::{
+    streaming::ChatStreamAccumulator,
     tools::{DynTool, Tool, ToolContext},
     types::{
         chat::{
-            ChatRequestMessage, ChatStreamEvent, FunctionalTool, RegularChatRequestMessage,
-            StreamingChatRequest, ToolCall, ToolCallResultMessage, ToolSpec,
+            ChatRequestMessage, ChatStream, ChatStreamEvent, FunctionalTool,
+            RegularChatRequestMessage, StreamingChatRequest, ToolCall, ToolCallResultMessage,
+            ToolSpec,
         },
         Role,
     },
@@ -107,91 +109,86 @@ async fn dispatch_tool_call(
     }
 }
 
+/// Print a tool call marker inline with the streamed content.
+fn print_tool_call(call: &ToolCall) {
+    match FibonacciTool::parse_n(&call.function.arguments) {
+        Ok(n) if call.function.name == "fibonacci" => {
+            print!("[tool call: {}(n={})]", call.function.name, n)
+        }
+        _ => print!("[tool call: {}]", call.function.name),
+    }
+}
+
 /// Consume the stream for one assistant message. Returns `Ok(true)` if a tool
 /// was called (and its result added to history), `Ok(false)` to finish.
-async fn process_stream<S>(
-    stream: &mut S,
+async fn process_stream(
+    mut stream: ChatStream,
     tools: &HashMap<String, DynTool>,
     history: &mut Vec<ChatRequestMessage>,
-) -> std::result::Result<bool, Error>
-where
-    S: futures::Stream<Item = std::result::Result<ChatStreamEvent, Error>> + Unpin,
-{
-    let mut assistant_buffer = String::new();
+) -> std::result::Result<bool, Error> {
+    // Stream the chunks and tool calls while the accumulator builds the complete message.
+    let mut prefix_printed = false;
     let mut seen_tool_call_ids = HashSet::new();
-    let mut collected_calls: Vec<ToolCall> = Vec::new();
-    let mut assistant_prefix_printed = false;
+    let mut accumulator = ChatStreamAccumulator::new().on_delta(move |chunk| {
+        if !prefix_printed {
+            print!("assistant: ");
+            prefix_printed = true;
+        }
+        print!("{}", chunk.message.content);
+        for call in &chunk.message.tool_calls {
+            if seen_tool_call_ids.insert(call.id.clone()) {
+                print_tool_call(call);
+            }
+        }
+    });
 
+    // Bad chunks and stream errors are logged, and the rest of the stream is still read.
     while let Some(event_res) = stream.next().await {
         match event_res {
-            Ok(ChatStreamEvent::Message(response)) => {
-                if !assistant_prefix_printed {
-                    print!("assistant: ");
-                    assistant_prefix_printed = true;
-                }
-
-                // Stream the chunk and accumulate the assistant message.
-                print!("{}", response.message.content);
-                assistant_buffer.push_str(&response.message.content);
-
-                // Record any tool calls (deduplicated by id).
-                for call in &response.message.tool_calls {
-                    if seen_tool_call_ids.insert(call.id.clone()) {
-                        if call.function.name == "fibonacci" {
-                            if let Ok(n) = FibonacciTool::parse_n(&call.function.arguments) {
-                                print!("[tool call: {}(n={})]", call.function.name, n);
-                            } else {
-                                print!("[tool call: {}]", call.function.name);
-                            }
-                        } else {
-                            print!("[tool call: {}]", call.function.name);
-                        }
-                        collected_calls.push(call.clone());
-                    }
-                }
-
-                // Once the message is complete, either finish or dispatch the first tool.
-                if response.done {
-                    println!();
-                    let mut assistant_msg =
-                        RegularChatRequestMessage::new(Role::Assistant, assistant_buffer.clone());
-                    for call in &collected_calls {
-                        let func = FunctionalTool {
-                            name: call.function.name.clone(),
-                            description: None, // Description is missing here. Need to fix this later.
-                            parameters: call.function.arguments.clone(),
-                        };
-                        assistant_msg = assistant_msg.add_tool_call(func);
-                    }
-                    history.push(ChatRequestMessage::Message(assistant_msg));
-
-                    if collected_calls.is_empty() {
-                        println!("assistant: [done]");
-                        return Ok(false);
-                    }
-
-                    for call in &collected_calls {
-                        println!("assistant: [dispatching tool: {}]", call.function.name);
-                        let result_msg = dispatch_tool_call(call, tools).await;
-                        history.push(ChatRequestMessage::ToolCallResult(result_msg));
-                    }
-
-                    return Ok(true);
-                }
-            }
-            Ok(ChatStreamEvent::Error(err)) => {
-                eprintln!("server error chunk: {}", err);
-            }
+            Ok(ChatStreamEvent::Error(err)) => eprintln!("server error chunk: {}", err),
             Ok(ChatStreamEvent::Partial { partial, error }) => {
-                eprintln!("partial response: {} {:?}", partial, error);
-            }
-            Err(e) => {
-                eprintln!("streaming error: {}", e);
+                eprintln!("partial response: {} {:?}", partial, error)
             }
+            Ok(ChatStreamEvent::LineError(error)) => eprintln!("bad chunk: {}", error),
+            Ok(event) => accumulator.push(event)?,
+            Err(e) => eprintln!("streaming error: {}", e),
+        }
+        if accumulator.is_done() {
+            break;
         }
     }
+    if !accumulator.is_done() {
+        return Ok(false);
+    }
+    println!();
+    let response = accumulator.into_response();
+    let collected_calls = &response.message.tool_calls;
+
+    // Once the message is complete, either finish or dispatch the tools.
+    let mut assistant_msg =
+        RegularChatRequestMessage::new(Role::Assistant, response.message.content.clone());
+    for call in collected_calls {
+        let func = FunctionalTool {
+            name: call.function.name.clone(),
+            description: None, // Description is missing here. Need to fix this later.
+            parameters: call.function.arguments.clone(),
+        };
+        assistant_msg = assistant_msg.add_tool_call(func);
+    }
+    history.push(ChatRequestMessage::Message(assistant_msg));
+
+    if collected_calls.is_empty() {
+        println!("assistant: [done]");
+        return Ok(false);
+    }
+
+    for call in collected_calls {
+        println!("assistant: [dispatching tool: {}]", call.function.name);
+        let result_msg = dispatch_tool_call(call, tools).await;
+        history.push(ChatRequestMessage::ToolCallResult(result_msg));
+    }
 
-    Ok(false)
+    Ok(true)
 }
 
 #[tokio::main]
@@ -237,8 +234,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Keep sending updated history until no tool is invoked.
     loop {
         let request = build_request(&model, &history, tools.clone());
-        let mut stream = client.chat_stream(request).await?;
-        let tool_was_called = process_stream(&mut stream, &tool_map, &mut history).await?;
+        let stream = client.chat_stream(request).await?;
+        let tool_was_called = process_stream(stream, &tool_map, &mut history).await?;
         if !tool_was_called {
             break;
         }
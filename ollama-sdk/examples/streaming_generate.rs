@@ -19,7 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match event {
             Ok(val) => match val {
                 GenerateStreamEvent::MessageChunk(chunk) => print!("{}", chunk.response),
-                GenerateStreamEvent::Error(error) => println!("\nError Chunk: {}", error),
+                GenerateStreamEvent::LineError(error) => println!("\nError Chunk: {}", error),
                 _ => continue,
             },
             Err(e) => eprintln!("Chat Error: {}", e),
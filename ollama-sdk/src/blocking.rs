@@ -0,0 +1,359 @@
+//! A synchronous client for the Ollama API, enabled with the `blocking` feature.
+//!
+//! [`OllamaClient`] wraps the asynchronous [`crate::OllamaClient`] and drives it on an
+//! internal single-threaded tokio runtime, so it can be used from code that does not
+//! run a runtime of its own. Streaming methods return [`ChatStream`] and
+//! [`GenerateStream`] iterators instead of async streams.
+//!
+//! The blocking client must not be used from within an async runtime, since blocking
+//! on a future inside another runtime panics.
+//!
+//! ```no_run
+//! use ollama_sdk::blocking::OllamaClient;
+//! use ollama_sdk::types::chat::{RegularChatRequestMessage, StreamingChatRequest};
+//! use ollama_sdk::types::Role;
+//!
+//! # fn main() -> ollama_sdk::Result<()> {
+//! let client = OllamaClient::builder().build()?;
+//! let request = StreamingChatRequest::new("llama3".to_string()).add_regular_message(
+//!     RegularChatRequestMessage::new(Role::User, "Why is the sky blue?".to_string()),
+//! );
+//!
+//! for event in client.chat_stream(request)? {
+//!     println!("{:?}", event?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use tokio::runtime::Runtime;
+use tokio_util::sync::CancellationToken;
+
+use crate::limiter::Limits;
+use crate::parser::ParserConfig;
+use crate::streaming::StreamTimings;
+use crate::tools::{DynTool, ToolRegistry};
+use crate::transport::Transport;
+use crate::types::chat::{ChatResponse, ChatStreamEvent, SimpleChatRequest, StreamingChatRequest};
+use crate::types::generate::{
+    GenerateResponse, GenerateStreamEvent, SimpleGenerateRequest, StreamingGenerateRequest,
+};
+use crate::types::{ListModelsResponse, ListRunningModelsResponse};
+use crate::{Error, Result};
+
+/// A blocking client for interacting with the Ollama API.
+///
+/// Use [`OllamaClient::builder()`] to create a client, or convert an existing
+/// [`crate::OllamaClient`] with [`OllamaClient::new`]. Clones share the same runtime.
+#[derive(Clone)]
+pub struct OllamaClient {
+    inner: crate::OllamaClient,
+    runtime: Arc<Runtime>,
+}
+
+impl OllamaClient {
+    /// Returns a new [`OllamaClientBuilder`].
+    pub fn builder() -> OllamaClientBuilder {
+        OllamaClientBuilder {
+            inner: crate::OllamaClient::builder(),
+        }
+    }
+
+    /// Wraps an asynchronous client into a blocking one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the internal runtime cannot be created.
+    pub fn new(client: crate::OllamaClient) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Client(format!("Failed to create runtime: {}", e)))?;
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Returns the asynchronous client wrapped by this client.
+    pub fn as_async(&self) -> &crate::OllamaClient {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Returns a clone of the client whose requests are queued with the given priority.
+    ///
+    /// See [`crate::OllamaClient::with_priority`].
+    pub fn with_priority(&self, priority: i32) -> OllamaClient {
+        OllamaClient {
+            inner: self.inner.with_priority(priority),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Registers a dynamic tool with the client's tool registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tool`](variant@Error::Tool) if a tool with the same name is already registered.
+    pub fn register_tool(&mut self, tool: DynTool) -> Result<()> {
+        self.inner.register_tool(tool)
+    }
+
+    /// Unregisters a tool from the client's tool registry by its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tool`](variant@Error::Tool) if no tool with the given name is found.
+    pub fn unregister_tool(&mut self, name: &str) -> Result<()> {
+        self.inner.unregister_tool(name)
+    }
+
+    /// Sends a streaming chat request and returns an iterator over its events.
+    ///
+    /// See [`crate::OllamaClient::chat_stream`].
+    pub fn chat_stream(&self, request: StreamingChatRequest) -> Result<ChatStream> {
+        let stream = self.block_on(self.inner.chat_stream(request))?;
+        Ok(ChatStream {
+            inner: stream,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Sends a streaming chat request that can be cancelled through `token`.
+    ///
+    /// See [`crate::OllamaClient::chat_stream_with_cancel`].
+    pub fn chat_stream_with_cancel(
+        &self,
+        request: StreamingChatRequest,
+        token: CancellationToken,
+    ) -> Result<ChatStream> {
+        let stream = self.block_on(self.inner.chat_stream_with_cancel(request, token))?;
+        Ok(ChatStream {
+            inner: stream,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Sends a non-streaming chat request and waits for the complete response.
+    ///
+    /// See [`crate::OllamaClient::chat_simple`].
+    pub fn chat_simple(&self, request: SimpleChatRequest) -> Result<ChatResponse> {
+        self.block_on(self.inner.chat_simple(request))
+    }
+
+    /// Sends a non-streaming chat request that can be cancelled through `token`.
+    ///
+    /// See [`crate::OllamaClient::chat_simple_with_cancel`].
+    pub fn chat_simple_with_cancel(
+        &self,
+        request: SimpleChatRequest,
+        token: CancellationToken,
+    ) -> Result<ChatResponse> {
+        self.block_on(self.inner.chat_simple_with_cancel(request, token))
+    }
+
+    /// Sends a streaming generate request and returns an iterator over its events.
+    ///
+    /// See [`crate::OllamaClient::generate_stream`].
+    pub fn generate_stream(&self, request: StreamingGenerateRequest) -> Result<GenerateStream> {
+        let stream = self.block_on(self.inner.generate_stream(request))?;
+        Ok(GenerateStream {
+            inner: stream,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Sends a streaming generate request that can be cancelled through `token`.
+    ///
+    /// See [`crate::OllamaClient::generate_stream_with_cancel`].
+    pub fn generate_stream_with_cancel(
+        &self,
+        request: StreamingGenerateRequest,
+        token: CancellationToken,
+    ) -> Result<GenerateStream> {
+        let stream = self.block_on(self.inner.generate_stream_with_cancel(request, token))?;
+        Ok(GenerateStream {
+            inner: stream,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Sends a non-streaming generate request and waits for the complete response.
+    ///
+    /// See [`crate::OllamaClient::generate_simple`].
+    pub fn generate_simple(&self, request: SimpleGenerateRequest) -> Result<GenerateResponse> {
+        self.block_on(self.inner.generate_simple(request))
+    }
+
+    /// Sends a non-streaming generate request that can be cancelled through `token`.
+    ///
+    /// See [`crate::OllamaClient::generate_simple_with_cancel`].
+    pub fn generate_simple_with_cancel(
+        &self,
+        request: SimpleGenerateRequest,
+        token: CancellationToken,
+    ) -> Result<GenerateResponse> {
+        self.block_on(self.inner.generate_simple_with_cancel(request, token))
+    }
+
+    /// Lists all available models on the Ollama server.
+    ///
+    /// See [`crate::OllamaClient::list_models`].
+    pub fn list_models(&self) -> Result<ListModelsResponse> {
+        self.block_on(self.inner.list_models())
+    }
+
+    /// Lists all models that are currently running on the Ollama server.
+    ///
+    /// See [`crate::OllamaClient::list_running_models`].
+    pub fn list_running_models(&self) -> Result<ListRunningModelsResponse> {
+        self.block_on(self.inner.list_running_models())
+    }
+}
+
+/// A builder for constructing a blocking [`OllamaClient`].
+///
+/// This mirrors [`crate::OllamaClientBuilder`]; see its documentation for the defaults.
+pub struct OllamaClientBuilder {
+    inner: crate::OllamaClientBuilder,
+}
+
+impl OllamaClientBuilder {
+    /// Sets the base URL for the Ollama API.
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        Self {
+            inner: self.inner.base_url(base_url),
+        }
+    }
+
+    /// Connects to the Ollama server through the Unix domain socket at `path`.
+    #[cfg(all(unix, feature = "unix-socket"))]
+    pub fn unix_socket(self, path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            inner: self.inner.unix_socket(path),
+        }
+    }
+
+    /// Sets the API key for authentication with the Ollama API.
+    pub fn api_key(self, api_key: impl Into<String>) -> Self {
+        Self {
+            inner: self.inner.api_key(api_key),
+        }
+    }
+
+    /// Sets a custom [`ToolRegistry`] for the client.
+    pub fn tool_registry(self, registry: ToolRegistry) -> Self {
+        Self {
+            inner: self.inner.tool_registry(registry),
+        }
+    }
+
+    /// Sets a custom transport implementation for the client.
+    pub fn transport(self, transport: Arc<dyn Transport + Send + Sync>) -> Self {
+        Self {
+            inner: self.inner.transport(transport),
+        }
+    }
+
+    /// Sets client-side concurrency [`Limits`] for chat and generate requests.
+    pub fn limits(self, limits: Limits) -> Self {
+        Self {
+            inner: self.inner.limits(limits),
+        }
+    }
+
+    /// Sets the [`ParserConfig`] used for streaming chat and generate responses.
+    pub fn parser_config(self, config: ParserConfig) -> Self {
+        Self {
+            inner: self.inner.parser_config(config),
+        }
+    }
+
+    /// Builds the blocking [`OllamaClient`] with the configured options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the asynchronous client cannot be built (see
+    /// [`crate::OllamaClientBuilder::build`]) or the internal runtime cannot be created.
+    pub fn build(self) -> Result<OllamaClient> {
+        OllamaClient::new(self.inner.build()?)
+    }
+}
+
+/// A blocking iterator over the [`ChatStreamEvent`]s of a streaming chat completion.
+pub struct ChatStream {
+    inner: crate::types::chat::ChatStream,
+    runtime: Arc<Runtime>,
+}
+
+impl ChatStream {
+    /// Returns the underlying asynchronous stream.
+    pub fn into_async(self) -> crate::types::chat::ChatStream {
+        self.inner
+    }
+
+    /// Returns the latency measurements of the stream so far.
+    ///
+    /// See [`crate::types::chat::ChatStream::timings`].
+    pub fn timings(&self) -> Option<StreamTimings> {
+        self.inner.timings()
+    }
+
+    /// Consumes the stream and returns the complete response.
+    ///
+    /// See [`crate::types::chat::ChatStream::collect_response`].
+    pub fn collect_response(self) -> Result<ChatResponse> {
+        self.runtime.block_on(self.inner.collect_response())
+    }
+}
+
+impl Iterator for ChatStream {
+    type Item = Result<ChatStreamEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.inner.next())
+    }
+}
+
+/// A blocking iterator over the [`GenerateStreamEvent`]s of a streaming generation.
+pub struct GenerateStream {
+    inner: crate::types::generate::GenerateStream,
+    runtime: Arc<Runtime>,
+}
+
+impl GenerateStream {
+    /// Returns the underlying asynchronous stream.
+    pub fn into_async(self) -> crate::types::generate::GenerateStream {
+        self.inner
+    }
+
+    /// Returns the latency measurements of the stream so far.
+    ///
+    /// See [`crate::types::generate::GenerateStream::timings`].
+    pub fn timings(&self) -> Option<StreamTimings> {
+        self.inner.timings()
+    }
+
+    /// Consumes the stream and returns the complete response.
+    ///
+    /// See [`crate::types::generate::GenerateStream::collect_response`].
+    pub fn collect_response(self) -> Result<GenerateResponse> {
+        self.runtime.block_on(self.inner.collect_response())
+    }
+}
+
+impl Iterator for GenerateStream {
+    type Item = Result<GenerateStreamEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.inner.next())
+    }
+}
@@ -1,12 +1,15 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
-use reqwest::Url;
-
+use crate::limiter::{Limiter, Limits};
+use crate::parser::ParserConfig;
 use crate::tools::ToolRegistry;
-use crate::transport::{ReqwestTransport, Transport};
+use crate::transport::Transport;
+#[cfg(all(unix, feature = "unix-socket"))]
+use crate::transport::UnixSocketTransport;
 use crate::{Error, OllamaClient, Result};
 
 /// A builder for constructing an [`OllamaClient`].
@@ -16,14 +19,21 @@ use crate::{Error, OllamaClient, Result};
 /// and a custom transport layer.
 ///
 /// - Uses either `OLLAMA_HOST` environment variable or `http://127.0.0.1:11434`.
+///   A `unix:///path/to/socket` host selects [`UnixSocketTransport`](crate::transport::UnixSocketTransport)
+///   (requires the `unix-socket` feature, enabled by default).
 /// - Uses either `OLLAMA_API_KEY` environment variable or nothing.
 /// - Starts with an empty [`ToolRegistry`] which can be populated later through [`OllamaClient`].
-/// - Uses `reqwest`-based transport by default - [`ReqwestTransport`].
+/// - Uses `reqwest`-based transport by default - [`ReqwestTransport`](crate::transport::ReqwestTransport).
+///   Without the `reqwest` feature, `HyperTransport` is used if the `hyper` feature is enabled;
+///   otherwise a custom [`transport`](OllamaClientBuilder::transport) is required.
 pub struct OllamaClientBuilder {
     base_url: Option<String>,
     api_key: Option<String>,
+    unix_socket: Option<PathBuf>,
     tool_registry: ToolRegistry,
     transport: Option<Arc<dyn Transport + Send + Sync>>,
+    limits: Option<Limits>,
+    parser_config: ParserConfig,
 }
 
 impl OllamaClientBuilder {
@@ -32,8 +42,11 @@ impl OllamaClientBuilder {
         OllamaClientBuilder {
             base_url: None,
             api_key: None,
+            unix_socket: None,
             tool_registry: ToolRegistry::new(),
             transport: None,
+            limits: None,
+            parser_config: ParserConfig::default(),
         }
     }
 
@@ -46,6 +59,16 @@ impl OllamaClientBuilder {
         self
     }
 
+    /// Connects to the Ollama server through the Unix domain socket at `path`.
+    ///
+    /// This takes precedence over [`base_url`](OllamaClientBuilder::base_url) and the
+    /// `OLLAMA_HOST` environment variable, and uses [`UnixSocketTransport`] under the hood.
+    #[cfg(all(unix, feature = "unix-socket"))]
+    pub fn unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
     /// Sets the API key for authentication with the Ollama API.
     ///
     /// If not set, the builder will try to read from the `OLLAMA_API_KEY` environment variable.
@@ -70,7 +93,7 @@ impl OllamaClientBuilder {
     /// Sets a custom transport implementation for the client.
     ///
     /// This allows for using different HTTP clients or mock implementations for testing.
-    /// If not set, a `reqwest`-based transport \([`ReqwestTransport`]\) will be used.
+    /// If not set, a `reqwest`-based transport \([`ReqwestTransport`](crate::transport::ReqwestTransport)\) will be used.
     ///
     /// For testing, you can use [`MockTransport`](crate::transport::MockTransport)
     /// or your own mock [`Transport`] implementations.
@@ -79,6 +102,24 @@ impl OllamaClientBuilder {
         self
     }
 
+    /// Sets client-side concurrency [`Limits`] for chat and generate requests.
+    ///
+    /// Requests beyond the limits are queued until a permit is available. Clones of the
+    /// built client share the same limits. If not set, requests are never queued.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Sets the [`ParserConfig`] used for streaming chat and generate responses.
+    ///
+    /// Use it to bound line length, buffering and event count when talking to servers
+    /// that are not fully trusted. If not set, streams are parsed without limits.
+    pub fn parser_config(mut self, config: ParserConfig) -> Self {
+        self.parser_config = config;
+        self
+    }
+
     /// Builds the [`OllamaClient`] with the configured options.
     ///
     /// If no transport is provided, it constructs a default `reqwest`-based transport
@@ -87,8 +128,9 @@ impl OllamaClientBuilder {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error::Client`](variant@Error::Client) if the base URL is invalid or if there's an issue
-    /// initializing [`ReqwestTransport`].
+    /// Returns an [`Error::Client`](variant@Error::Client) if the base URL is invalid, if there's an issue
+    /// initializing the default transport, or if no transport was provided and neither the
+    /// `reqwest` nor the `hyper` feature is enabled.
     #[cfg_attr(feature = "tracing", instrument(skip(self)))]
     pub fn build(self) -> Result<OllamaClient> {
         let transport = if let Some(t) = self.transport {
@@ -102,15 +144,81 @@ impl OllamaClientBuilder {
                 .api_key
                 .or_else(|| std::env::var("OLLAMA_API_KEY").ok());
 
-            let base_url = Url::parse(&base_url_str)
-                .map_err(|e| Error::Client(format!("Invalid base URL: {}", e)))?;
+            let unix_socket = self
+                .unix_socket
+                .or_else(|| base_url_str.strip_prefix("unix://").map(PathBuf::from));
 
-            Arc::new(ReqwestTransport::new(base_url, api_key)?)
+            if let Some(path) = unix_socket {
+                unix_socket_transport(path, api_key)?
+            } else {
+                http_transport(&base_url_str, api_key)?
+            }
         };
 
         Ok(OllamaClient {
             transport,
             tool_registry: self.tool_registry,
+            limiter: self.limits.map(|limits| Arc::new(Limiter::new(limits))),
+            priority: 0,
+            parser_config: self.parser_config,
         })
     }
 }
+
+/// Constructs a [`UnixSocketTransport`] for the socket at `path`.
+#[cfg(all(unix, feature = "unix-socket"))]
+fn unix_socket_transport(
+    path: PathBuf,
+    api_key: Option<String>,
+) -> Result<Arc<dyn Transport + Send + Sync>> {
+    Ok(Arc::new(UnixSocketTransport::new(path, api_key)))
+}
+
+/// Fails because Unix domain sockets are not supported by this build.
+#[cfg(not(all(unix, feature = "unix-socket")))]
+fn unix_socket_transport(
+    path: PathBuf,
+    _api_key: Option<String>,
+) -> Result<Arc<dyn Transport + Send + Sync>> {
+    Err(Error::Client(format!(
+        "Unix socket host '{}' requires the `unix-socket` feature on a Unix platform",
+        path.display()
+    )))
+}
+
+/// Constructs the default HTTP transport for `base_url`, preferring `reqwest` over `hyper`.
+#[cfg(feature = "reqwest")]
+pub(crate) fn http_transport(
+    base_url: &str,
+    api_key: Option<String>,
+) -> Result<Arc<dyn Transport + Send + Sync>> {
+    let base_url = reqwest::Url::parse(base_url)
+        .map_err(|e| Error::Client(format!("Invalid base URL: {}", e)))?;
+    Ok(Arc::new(crate::transport::ReqwestTransport::new(
+        base_url, api_key,
+    )?))
+}
+
+/// Constructs the default HTTP transport for `base_url` on top of `hyper`.
+#[cfg(all(not(feature = "reqwest"), feature = "hyper"))]
+pub(crate) fn http_transport(
+    base_url: &str,
+    api_key: Option<String>,
+) -> Result<Arc<dyn Transport + Send + Sync>> {
+    Ok(Arc::new(crate::transport::HyperTransport::new(
+        base_url, api_key,
+    )?))
+}
+
+/// Fails because this build has no HTTP stack.
+#[cfg(not(any(feature = "reqwest", feature = "hyper")))]
+pub(crate) fn http_transport(
+    base_url: &str,
+    _api_key: Option<String>,
+) -> Result<Arc<dyn Transport + Send + Sync>> {
+    Err(Error::Client(format!(
+        "Cannot connect to '{}' without an HTTP transport: enable the `reqwest` or `hyper` \
+         feature, or provide a custom transport",
+        base_url
+    )))
+}
@@ -5,7 +5,11 @@ use metrics::counter;
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
-use crate::parser::GenericStreamParser;
+use tokio_util::sync::CancellationToken;
+
+use crate::limiter::{Limiter, Permit, PermitStream};
+use crate::parser::{GenericStreamParser, ParserConfig};
+use crate::streaming::{CancellableStream, StreamTimer};
 use crate::tools::{DynTool, ToolRegistry};
 use crate::transport::Transport;
 use crate::types::chat::{
@@ -25,6 +29,9 @@ use crate::{Error, OllamaClientBuilder, Result};
 pub struct OllamaClient {
     pub(crate) transport: Arc<dyn Transport + Send + Sync>,
     pub(crate) tool_registry: ToolRegistry,
+    pub(crate) limiter: Option<Arc<Limiter>>,
+    pub(crate) priority: i32,
+    pub(crate) parser_config: ParserConfig,
 }
 
 impl OllamaClient {
@@ -33,6 +40,25 @@ impl OllamaClient {
         OllamaClientBuilder::new()
     }
 
+    /// Returns a clone of the client whose requests are queued with the given priority.
+    ///
+    /// When the client's [`Limits`](crate::limiter::Limits) are reached, queued requests
+    /// with a higher priority are served first. The default priority is `0`.
+    pub fn with_priority(&self, priority: i32) -> OllamaClient {
+        OllamaClient {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Waits for a limiter permit for a request to `model`, if the client has limits.
+    async fn acquire_permit(&self, model: &str) -> Result<Option<Permit>> {
+        match &self.limiter {
+            Some(limiter) => limiter.acquire(model, self.priority).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Registers a dynamic tool with the client's tool registry.
     ///
     /// This allows the client to use the registered tool in tool-calling scenarios.
@@ -71,19 +97,55 @@ impl OllamaClient {
     /// # Arguments
     ///
     /// * `request` - The [`StreamingChatRequest`] containing the chat messages and model.
-    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
     pub async fn chat_stream(&self, request: StreamingChatRequest) -> Result<ChatStream> {
+        self.chat_stream_with_cancel(request, CancellationToken::new())
+            .await
+    }
+
+    /// Sends a streaming chat request that can be cancelled through `token`.
+    ///
+    /// Cancelling the token aborts the HTTP request. If the stream was already
+    /// returned, it yields a final [`Error::Cancelled`] reporting how many chunks with
+    /// generated tokens were received, and then ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`StreamingChatRequest`] containing the chat messages and model.
+    /// * `token` - The [`CancellationToken`] that cancels the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Cancelled`] if the token is cancelled before the response arrives.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request, token)))]
+    pub async fn chat_stream_with_cancel(
+        &self,
+        request: StreamingChatRequest,
+        token: CancellationToken,
+    ) -> Result<ChatStream> {
         #[cfg(feature = "metrics")]
         counter!("ollama_client.chat_requests_total", "type" => "streaming").increment(1);
 
         let chat_request = ChatRequest::from(request);
-        let request = HttpRequest::new("/api/chat").post().body(chat_request)?;
-
-        let byte_stream = self.transport.send_http_stream_request(request).await?;
-        let parser = GenericStreamParser::<_, ChatResponse, ChatStreamEvent>::new(byte_stream);
+        let (permit, mut timer, response) = cancellable(&token, async {
+            let permit = self.acquire_permit(&chat_request.model).await?;
+            let timer = StreamTimer::start(&chat_request.model);
+            let request = HttpRequest::new("/api/chat").post().body(chat_request)?;
+            let response = self.transport.send_http_stream_request(request).await?;
+            Ok((permit, timer, response))
+        })
+        .await?;
+        let config = self.parser_config.framing_for(response.content_type());
+        let parser = GenericStreamParser::<_, ChatResponse, ChatStreamEvent>::with_config(
+            timer.body(response.body),
+            config,
+        );
 
         Ok(ChatStream {
-            inner: Box::pin(parser),
+            inner: Box::pin(CancellableStream::new(
+                PermitStream::new(parser, permit),
+                token,
+            )),
+            timer: Some(timer),
         })
     }
 
@@ -95,15 +157,39 @@ impl OllamaClient {
     /// # Arguments
     ///
     /// * `request` - The [`SimpleChatRequest`] containing the chat messages and model.
-    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
     pub async fn chat_simple(&self, request: SimpleChatRequest) -> Result<ChatResponse> {
+        self.chat_simple_with_cancel(request, CancellationToken::new())
+            .await
+    }
+
+    /// Sends a non-streaming chat request that can be cancelled through `token`.
+    ///
+    /// Cancelling the token aborts the HTTP request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`SimpleChatRequest`] containing the chat messages and model.
+    /// * `token` - The [`CancellationToken`] that cancels the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Cancelled`] if the token is cancelled before the response arrives.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request, token)))]
+    pub async fn chat_simple_with_cancel(
+        &self,
+        request: SimpleChatRequest,
+        token: CancellationToken,
+    ) -> Result<ChatResponse> {
         #[cfg(feature = "metrics")]
         counter!("ollama_client.chat_requests_total", "type" => "non_streaming").increment(1);
 
         let chat_request = ChatRequest::from(request);
-        let request = HttpRequest::new("/api/chat").post().body(chat_request)?;
-
-        let response = self.transport.send_http_request(request).await?;
+        let response = cancellable(&token, async {
+            let _permit = self.acquire_permit(&chat_request.model).await?;
+            let request = HttpRequest::new("/api/chat").post().body(chat_request)?;
+            self.transport.send_http_request(request).await
+        })
+        .await?;
 
         match response.body {
             Some(bytes) => ChatResponse::from_bytes(bytes),
@@ -119,25 +205,60 @@ impl OllamaClient {
     /// # Arguments
     ///
     /// * `request` - The [`StreamingGenerateRequest`] containing the prompt and model.
-    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
     pub async fn generate_stream(
         &self,
         request: StreamingGenerateRequest,
+    ) -> Result<GenerateStream> {
+        self.generate_stream_with_cancel(request, CancellationToken::new())
+            .await
+    }
+
+    /// Sends a streaming generate request that can be cancelled through `token`.
+    ///
+    /// Cancelling the token aborts the HTTP request. If the stream was already
+    /// returned, it yields a final [`Error::Cancelled`] reporting how many chunks with
+    /// generated tokens were received, and then ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`StreamingGenerateRequest`] containing the prompt and model.
+    /// * `token` - The [`CancellationToken`] that cancels the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Cancelled`] if the token is cancelled before the response arrives.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request, token)))]
+    pub async fn generate_stream_with_cancel(
+        &self,
+        request: StreamingGenerateRequest,
+        token: CancellationToken,
     ) -> Result<GenerateStream> {
         #[cfg(feature = "metrics")]
         counter!("ollama_client.generate_requests_total", "type" => "streaming").increment(1);
 
         let generate_request = GenerateRequest::from(request);
-        let request = HttpRequest::new("/api/generate")
-            .post()
-            .body(generate_request)?;
-
-        let byte_stream = self.transport.send_http_stream_request(request).await?;
-        let parser =
-            GenericStreamParser::<_, GenerateResponse, GenerateStreamEvent>::new(byte_stream);
+        let (permit, mut timer, response) = cancellable(&token, async {
+            let permit = self.acquire_permit(&generate_request.model).await?;
+            let timer = StreamTimer::start(&generate_request.model);
+            let request = HttpRequest::new("/api/generate")
+                .post()
+                .body(generate_request)?;
+            let response = self.transport.send_http_stream_request(request).await?;
+            Ok((permit, timer, response))
+        })
+        .await?;
+        let config = self.parser_config.framing_for(response.content_type());
+        let parser = GenericStreamParser::<_, GenerateResponse, GenerateStreamEvent>::with_config(
+            timer.body(response.body),
+            config,
+        );
 
         Ok(GenerateStream {
-            inner: Box::pin(parser),
+            inner: Box::pin(CancellableStream::new(
+                PermitStream::new(parser, permit),
+                token,
+            )),
+            timer: Some(timer),
         })
     }
 
@@ -149,20 +270,44 @@ impl OllamaClient {
     /// # Arguments
     ///
     /// * `request` - The [`SimpleGenerateRequest`] containing the prompt and model.
-    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
     pub async fn generate_simple(
         &self,
         request: SimpleGenerateRequest,
+    ) -> Result<GenerateResponse> {
+        self.generate_simple_with_cancel(request, CancellationToken::new())
+            .await
+    }
+
+    /// Sends a non-streaming generate request that can be cancelled through `token`.
+    ///
+    /// Cancelling the token aborts the HTTP request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`SimpleGenerateRequest`] containing the prompt and model.
+    /// * `token` - The [`CancellationToken`] that cancels the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Cancelled`] if the token is cancelled before the response arrives.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request, token)))]
+    pub async fn generate_simple_with_cancel(
+        &self,
+        request: SimpleGenerateRequest,
+        token: CancellationToken,
     ) -> Result<GenerateResponse> {
         #[cfg(feature = "metrics")]
         counter!("ollama_client.generate_requests_total", "type" => "non_streaming").increment(1);
 
         let generate_request = GenerateRequest::from(request);
-        let request = HttpRequest::new("/api/generate")
-            .post()
-            .body(generate_request)?;
-
-        let response = self.transport.send_http_request(request).await?;
+        let response = cancellable(&token, async {
+            let _permit = self.acquire_permit(&generate_request.model).await?;
+            let request = HttpRequest::new("/api/generate")
+                .post()
+                .body(generate_request)?;
+            self.transport.send_http_request(request).await
+        })
+        .await?;
 
         match response.body {
             Some(bytes) => GenerateResponse::from_bytes(bytes),
@@ -200,3 +345,17 @@ impl OllamaClient {
         }
     }
 }
+
+/// Runs `future` until it completes or `token` is cancelled, whichever happens first.
+///
+/// The future is dropped on cancellation, which aborts any request it was sending.
+async fn cancellable<T, F>(token: &CancellationToken, future: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(Error::Cancelled { chunks_received: 0 }),
+        result = future => result,
+    }
+}
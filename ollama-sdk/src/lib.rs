@@ -8,7 +8,9 @@
 //!
 //! - **Idiomatic Rust API:** Designed with Rust's best practices in mind.
 //! - **Streaming support**: Handle streaming responses for chat and generate operations efficiently.
-//! - **Configurable Transport:** Uses `reqwest` by default, with an extensible [`Transport`](crate::transport::Transport) trait for custom implementations.
+//! - **Configurable Transport:** Uses `reqwest` by default (`reqwest` feature), a lean `hyper`-only transport with the `hyper` feature, and an extensible [`Transport`](crate::transport::Transport) trait for custom implementations. With default features disabled, the core types, parser and client build without any HTTP stack.
+//! - **Unix Domain Sockets:** Connect through a socket file with `unix:///path/to/socket` hosts (`unix-socket` feature, enabled by default).
+//! - **Blocking Client:** A synchronous [`blocking::OllamaClient`](crate::blocking::OllamaClient) for code without an async runtime (`blocking` feature).
 //! - **Robust Error Handling:** Comprehensive error types for predictable error management.
 //! - **Observability:** Optional `tracing` for detailed logging and `metrics` for performance monitoring.
 //! - **Tooling Integration**: Support for tool definitions and registry.
@@ -21,20 +23,37 @@
 
 use thiserror::Error;
 
+// Lets the derive macros of `ollama-sdk-macros` refer to `::ollama_sdk` inside this crate.
+extern crate self as ollama_sdk;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod builder;
 mod client;
+pub mod limiter;
 pub mod parser;
+pub mod streaming;
 pub mod tools;
 pub mod transport;
 pub mod types;
 
 pub use crate::{builder::OllamaClientBuilder, client::OllamaClient};
 
+/// Re-exports used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use bytes::Bytes;
+    pub use futures::Stream;
+}
+
 /// An alias for [`std::result::Result<T, E>`] where E is [`enum@Error`].
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Represents errors that can occur when interacting with the Ollama API.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// A client-side error, typically due to invalid input or configuration.
     #[error("Client error: {0}")]
@@ -42,12 +61,22 @@ pub enum Error {
 
     /// An error originating from the underlying HTTP transport layer (e.g., network issues).
     #[error("Transport error: {0}")]
-    Transport(#[from] reqwest::Error),
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
 
     /// An error returned by the Ollama server.
     #[error("Server error: {0}")]
     Server(String),
 
+    /// The server answered a request with a non-success HTTP status.
+    #[error("HTTP {status}: {message}")]
+    Http {
+        /// The status code of the response.
+        status: http::StatusCode,
+        /// The Ollama error message of the response body, or the body itself if it is not
+        /// an Ollama error object.
+        message: String,
+    },
+
     /// An error during JSON serialization or deserialization.
     #[error("JSON error: {0}")]
     JsonParse(#[from] serde_json::Error),
@@ -60,7 +89,109 @@ pub enum Error {
     #[error("Tool error: {0}")]
     Tool(String),
 
-    /// The streaming operation was cancelled.
-    #[error("Stream cancelled")]
-    Cancelled,
+    /// The request or stream was cancelled through its
+    /// [`CancellationToken`](tokio_util::sync::CancellationToken).
+    #[error("Request cancelled after {chunks_received} chunks")]
+    Cancelled {
+        /// The number of message chunks carrying generated tokens that were received
+        /// before the cancellation.
+        chunks_received: usize,
+    },
+
+    /// The request was rejected without being sent because a
+    /// [`CircuitBreakerTransport`](crate::transport::CircuitBreakerTransport) is open.
+    #[error("Circuit breaker open, retry after {retry_after:?}")]
+    CircuitOpen {
+        /// How long until the circuit lets probe requests through again.
+        retry_after: std::time::Duration,
+    },
+
+    /// The request waited longer than the maximum queue wait of the client's
+    /// [`Limits`](crate::limiter::Limits) without getting a permit.
+    #[error("Request timed out in limiter queue after {waited:?}")]
+    QueueTimeout {
+        /// How long the request waited in the queue.
+        waited: std::time::Duration,
+    },
+
+    /// A response stream exceeded a limit of the client's
+    /// [`ParserConfig`](crate::parser::ParserConfig). The stream ends after this error.
+    #[error("Stream limit exceeded: more than {max} {limit}")]
+    StreamLimitExceeded {
+        /// The limit that was exceeded.
+        limit: crate::parser::StreamLimit,
+        /// The configured maximum.
+        max: usize,
+    },
+
+    /// An I/O error while reading or writing stream data.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A subscriber of a [`Broadcaster`](crate::streaming::Broadcaster) fell behind the
+    /// stream by more than its buffer capacity and was dropped. The subscriber ends after
+    /// this error.
+    #[error("Stream subscriber dropped after falling {capacity} events behind")]
+    SubscriberLagged {
+        /// The buffer capacity of the subscriber.
+        capacity: usize,
+    },
+
+    /// A line of a response stream was not valid JSON or did not match the expected
+    /// message, and [`ParserConfig::strict`](crate::parser::ParserConfig::strict) is set.
+    /// The stream ends after this error.
+    #[error("Malformed stream {0}")]
+    MalformedLine(Box<crate::parser::StreamLineError>),
+}
+
+impl Error {
+    /// Returns the HTTP status code of an [`Error::Http`].
+    pub fn status(&self) -> Option<http::StatusCode> {
+        match self {
+            Error::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Creates a copy of the error for another consumer of the same stream.
+    ///
+    /// Errors from other libraries cannot be cloned, so the copy of an
+    /// [`Error::Transport`] or [`Error::JsonParse`] only keeps its message.
+    pub(crate) fn duplicate(&self) -> Error {
+        match self {
+            Error::Client(message) => Error::Client(message.clone()),
+            Error::Transport(source) => Error::Transport(source.to_string().into()),
+            Error::Server(message) => Error::Server(message.clone()),
+            Error::Http { status, message } => Error::Http {
+                status: *status,
+                message: message.clone(),
+            },
+            Error::JsonParse(source) => Error::Protocol(source.to_string()),
+            Error::Protocol(message) => Error::Protocol(message.clone()),
+            Error::Tool(message) => Error::Tool(message.clone()),
+            Error::Cancelled { chunks_received } => Error::Cancelled {
+                chunks_received: *chunks_received,
+            },
+            Error::CircuitOpen { retry_after } => Error::CircuitOpen {
+                retry_after: *retry_after,
+            },
+            Error::QueueTimeout { waited } => Error::QueueTimeout { waited: *waited },
+            Error::StreamLimitExceeded { limit, max } => Error::StreamLimitExceeded {
+                limit: *limit,
+                max: *max,
+            },
+            Error::Io(source) => Error::Io(std::io::Error::new(source.kind(), source.to_string())),
+            Error::SubscriberLagged { capacity } => Error::SubscriberLagged {
+                capacity: *capacity,
+            },
+            Error::MalformedLine(error) => Error::MalformedLine(error.clone()),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(Box::new(err))
+    }
 }
@@ -0,0 +1,304 @@
+//! Client-side concurrency limits for model requests.
+//!
+//! A single GPU can only serve a few generations at once, and requests beyond that
+//! mostly add latency. [`Limits`] configured through
+//! [`OllamaClientBuilder::limits`](crate::OllamaClientBuilder::limits) cap the number of
+//! chat and generate requests in flight, globally and per model, and queue the rest.
+//!
+//! Queued requests are served by priority (see [`OllamaClient::with_priority`](crate::OllamaClient::with_priority)),
+//! then in arrival order. A request whose model is at its cap does not hold up requests
+//! for other models. The permit of a streaming request is held until its
+//! [`ChatStream`](crate::types::chat::ChatStream) or
+//! [`GenerateStream`](crate::types::generate::GenerateStream) ends or is dropped.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "metrics")]
+use metrics::counter;
+
+use futures::Stream;
+use tokio::sync::oneshot;
+
+use crate::{Error, Result};
+
+/// Concurrency limits for an [`OllamaClient`](crate::OllamaClient).
+///
+/// All limits are unset by default, in which case requests are never queued.
+///
+/// ```
+/// use std::time::Duration;
+/// use ollama_sdk::limiter::Limits;
+///
+/// let limits = Limits::new()
+///     .max_in_flight(8)
+///     .max_in_flight_per_model(2)
+///     .model_max_in_flight("llama3:70b", 1)
+///     .max_queue_wait(Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    max_in_flight: Option<usize>,
+    max_in_flight_per_model: Option<usize>,
+    model_max_in_flight: HashMap<String, usize>,
+    max_queue_wait: Option<Duration>,
+}
+
+impl Limits {
+    /// Creates a new [`Limits`] without any limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the number of requests in flight across all models.
+    pub fn max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max.max(1));
+        self
+    }
+
+    /// Caps the number of requests in flight for each model without a specific cap.
+    pub fn max_in_flight_per_model(mut self, max: usize) -> Self {
+        self.max_in_flight_per_model = Some(max.max(1));
+        self
+    }
+
+    /// Caps the number of requests in flight for `model`, overriding
+    /// [`max_in_flight_per_model`](Limits::max_in_flight_per_model).
+    pub fn model_max_in_flight(mut self, model: impl Into<String>, max: usize) -> Self {
+        self.model_max_in_flight.insert(model.into(), max.max(1));
+        self
+    }
+
+    /// Fails requests with [`Error::QueueTimeout`] if they waited in the queue for longer than `wait`.
+    pub fn max_queue_wait(mut self, wait: Duration) -> Self {
+        self.max_queue_wait = Some(wait);
+        self
+    }
+
+    fn model_cap(&self, model: &str) -> Option<usize> {
+        self.model_max_in_flight
+            .get(model)
+            .copied()
+            .or(self.max_in_flight_per_model)
+    }
+}
+
+/// Tracks in-flight requests and hands out permits to queued ones.
+pub(crate) struct Limiter {
+    limits: Limits,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    in_flight: usize,
+    model_in_flight: HashMap<String, usize>,
+    queue: Vec<Waiter>,
+    next_seq: u64,
+}
+
+struct Waiter {
+    seq: u64,
+    priority: i32,
+    model: String,
+    granted: oneshot::Sender<()>,
+}
+
+impl LimiterState {
+    fn has_capacity(&self, limits: &Limits, model: &str) -> bool {
+        limits
+            .max_in_flight
+            .map_or(true, |max| self.in_flight < max)
+            && limits.model_cap(model).map_or(true, |max| {
+                self.model_in_flight.get(model).copied().unwrap_or(0) < max
+            })
+    }
+
+    fn take(&mut self, model: &str) {
+        self.in_flight += 1;
+        *self.model_in_flight.entry(model.to_string()).or_insert(0) += 1;
+    }
+
+    fn release(&mut self, model: &str) {
+        self.in_flight -= 1;
+        if let Some(count) = self.model_in_flight.get_mut(model) {
+            *count -= 1;
+            if *count == 0 {
+                self.model_in_flight.remove(model);
+            }
+        }
+    }
+
+    /// Grants permits to queued requests, highest priority first, while there is capacity.
+    fn dispatch(&mut self, limits: &Limits) {
+        self.queue
+            .sort_by_key(|waiter| (std::cmp::Reverse(waiter.priority), waiter.seq));
+
+        let mut index = 0;
+        while index < self.queue.len() {
+            if limits
+                .max_in_flight
+                .is_some_and(|max| self.in_flight >= max)
+            {
+                break;
+            }
+            if !self.has_capacity(limits, &self.queue[index].model) {
+                index += 1;
+                continue;
+            }
+
+            let waiter = self.queue.remove(index);
+            self.take(&waiter.model);
+            if waiter.granted.send(()).is_err() {
+                // The waiting request went away; give the slot to the next one.
+                self.release(&waiter.model);
+            }
+        }
+    }
+}
+
+impl Limiter {
+    pub(crate) fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// Waits for a permit to send a request for `model`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::QueueTimeout`] if the configured maximum queue wait elapsed first.
+    pub(crate) async fn acquire(self: &Arc<Self>, model: &str, priority: i32) -> Result<Permit> {
+        let (receiver, seq) = {
+            let mut state = self.state.lock().unwrap();
+            let (sender, receiver) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.queue.push(Waiter {
+                seq,
+                priority,
+                model: model.to_string(),
+                granted: sender,
+            });
+            state.dispatch(&self.limits);
+            (receiver, seq)
+        };
+
+        let mut queued = Queued {
+            limiter: self,
+            seq,
+            model,
+            receiver,
+            waiting: true,
+        };
+        let started = Instant::now();
+        let granted = match self.limits.max_queue_wait {
+            Some(wait) => tokio::time::timeout(wait, &mut queued.receiver)
+                .await
+                .is_ok(),
+            None => {
+                let _ = (&mut queued.receiver).await;
+                true
+            }
+        };
+
+        // A request granted right as its wait ran out keeps the permit.
+        if granted || !queued.leave_queue() {
+            queued.waiting = false;
+            return Ok(Permit {
+                limiter: self.clone(),
+                model: model.to_string(),
+            });
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(model, priority, "request timed out in limiter queue");
+        #[cfg(feature = "metrics")]
+        counter!("ollama_client.limiter_queue_timeouts_total").increment(1);
+
+        queued.waiting = false;
+        Err(Error::QueueTimeout {
+            waited: started.elapsed(),
+        })
+    }
+}
+
+/// A request waiting for a permit, cleaned up if the wait is abandoned.
+struct Queued<'a> {
+    limiter: &'a Arc<Limiter>,
+    seq: u64,
+    model: &'a str,
+    /// Kept alive until the request left the queue, so that a grant is never lost.
+    receiver: oneshot::Receiver<()>,
+    waiting: bool,
+}
+
+impl Queued<'_> {
+    /// Removes the request from the queue, returning `false` if it was already granted.
+    fn leave_queue(&mut self) -> bool {
+        let mut state = self.limiter.state.lock().unwrap();
+        match state.queue.iter().position(|w| w.seq == self.seq) {
+            Some(index) => {
+                state.queue.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        // The request was cancelled while waiting. If it was granted in the meantime,
+        // give the slot to the next request.
+        if self.waiting && !self.leave_queue() {
+            let mut state = self.limiter.state.lock().unwrap();
+            state.release(self.model);
+            state.dispatch(&self.limiter.limits);
+        }
+    }
+}
+
+/// A slot taken from the [`Limiter`], given back when dropped.
+pub(crate) struct Permit {
+    limiter: Arc<Limiter>,
+    model: String,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        state.release(&self.model);
+        state.dispatch(&self.limiter.limits);
+    }
+}
+
+/// A stream holding a [`Permit`] until it ends or is dropped.
+pub(crate) struct PermitStream<S> {
+    inner: S,
+    permit: Option<Permit>,
+}
+
+impl<S> PermitStream<S> {
+    pub(crate) fn new(inner: S, permit: Option<Permit>) -> Self {
+        Self { inner, permit }
+    }
+}
+
+impl<S: Stream + Unpin> Stream for PermitStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let item = futures::ready!(Pin::new(&mut this.inner).poll_next(cx));
+        if item.is_none() {
+            this.permit = None;
+        }
+        Poll::Ready(item)
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::streaming::{line_failure, unparseable};
+use crate::types::chat::{ChatResponse, ChatStreamEvent};
+use crate::types::generate::{GenerateResponse, GenerateStreamEvent};
+use crate::{Error, Result};
+
+/// A callback observing the chunks pushed into an accumulator.
+type DeltaCallback<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Builds a complete [`ChatResponse`] out of the events of a [`ChatStream`](crate::types::chat::ChatStream).
+///
+/// Content and thinking are concatenated, tool calls are collected once per id, and the
+/// `done` flag, `done_reason` and stats are taken from the final chunk. An optional
+/// callback observes every chunk as it is pushed.
+///
+/// [`ChatStream::collect_response`](crate::types::chat::ChatStream::collect_response) drives
+/// an accumulator over a whole stream.
+#[derive(Default)]
+pub struct ChatStreamAccumulator {
+    response: ChatResponse,
+    seen_tool_calls: HashSet<String>,
+    on_delta: Option<DeltaCallback<ChatResponse>>,
+}
+
+impl ChatStreamAccumulator {
+    /// Creates an empty [`ChatStreamAccumulator`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback that is called with every message chunk before it is accumulated.
+    pub fn on_delta<F>(mut self, on_delta: F) -> Self
+    where
+        F: FnMut(&ChatResponse) + Send + 'static,
+    {
+        self.on_delta = Some(Box::new(on_delta));
+        self
+    }
+
+    /// Adds a stream event to the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Server`] for an error event or a line error sent by the server,
+    /// an [`Error::MalformedLine`] for any other line error and an [`Error::Protocol`] for
+    /// a partial event, leaving the accumulated response unchanged.
+    pub fn push(&mut self, event: ChatStreamEvent) -> Result<()> {
+        let chunk = match event {
+            ChatStreamEvent::Message(chunk) => chunk,
+            ChatStreamEvent::Error(error) => return Err(Error::Server(error)),
+            ChatStreamEvent::Partial { partial, .. } => return Err(unparseable(partial)),
+            ChatStreamEvent::LineError(error) => return Err(line_failure(error)),
+        };
+        if let Some(on_delta) = &mut self.on_delta {
+            on_delta(&chunk);
+        }
+
+        let response = &mut self.response;
+        if response.model.is_empty() {
+            response.model = chunk.model;
+            response.message.role = chunk.message.role;
+        }
+        response.created_at = chunk.created_at;
+        response.message.content.push_str(&chunk.message.content);
+        response.message.thinking.push_str(&chunk.message.thinking);
+        for call in chunk.message.tool_calls {
+            if self.seen_tool_calls.insert(call.id.clone()) {
+                response.message.tool_calls.push(call);
+            }
+        }
+        if chunk.done {
+            response.done = true;
+            response.done_reason = chunk.done_reason;
+            response.total_duration = chunk.total_duration;
+            response.load_duration = chunk.load_duration;
+            response.prompt_eval_count = chunk.prompt_eval_count;
+            response.prompt_eval_duration = chunk.prompt_eval_duration;
+            response.eval_count = chunk.eval_count;
+            response.eval_duration = chunk.eval_duration;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` once the final chunk of the response was pushed.
+    pub fn is_done(&self) -> bool {
+        self.response.done
+    }
+
+    /// Returns the response accumulated so far.
+    pub fn response(&self) -> &ChatResponse {
+        &self.response
+    }
+
+    /// Returns the accumulated response, whether or not the final chunk was pushed.
+    pub fn into_response(self) -> ChatResponse {
+        self.response
+    }
+
+    /// Returns the complete response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Protocol`] if the final chunk of the response was never pushed.
+    pub fn finish(self) -> Result<ChatResponse> {
+        if !self.is_done() {
+            return Err(Error::Protocol(
+                "Chat stream ended before the final chunk".to_string(),
+            ));
+        }
+        Ok(self.response)
+    }
+}
+
+impl fmt::Debug for ChatStreamAccumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChatStreamAccumulator")
+            .field("response", &self.response)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds a complete [`GenerateResponse`] out of the events of a
+/// [`GenerateStream`](crate::types::generate::GenerateStream).
+///
+/// This is the generate counterpart of [`ChatStreamAccumulator`].
+#[derive(Default)]
+pub struct GenerateStreamAccumulator {
+    response: GenerateResponse,
+    on_delta: Option<DeltaCallback<GenerateResponse>>,
+}
+
+impl GenerateStreamAccumulator {
+    /// Creates an empty [`GenerateStreamAccumulator`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback that is called with every response chunk before it is accumulated.
+    pub fn on_delta<F>(mut self, on_delta: F) -> Self
+    where
+        F: FnMut(&GenerateResponse) + Send + 'static,
+    {
+        self.on_delta = Some(Box::new(on_delta));
+        self
+    }
+
+    /// Adds a stream event to the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Server`] for an error event or a line error sent by the server,
+    /// an [`Error::MalformedLine`] for any other line error and an [`Error::Protocol`] for
+    /// a partial event, leaving the accumulated response unchanged.
+    pub fn push(&mut self, event: GenerateStreamEvent) -> Result<()> {
+        let chunk = match event {
+            GenerateStreamEvent::MessageChunk(chunk) => chunk,
+            GenerateStreamEvent::Error(error) => return Err(Error::Server(error)),
+            GenerateStreamEvent::Partial { partial, .. } => return Err(unparseable(partial)),
+            GenerateStreamEvent::LineError(error) => return Err(line_failure(error)),
+        };
+        if let Some(on_delta) = &mut self.on_delta {
+            on_delta(&chunk);
+        }
+
+        let response = &mut self.response;
+        if response.model.is_empty() {
+            response.model = chunk.model;
+        }
+        response.created_at = chunk.created_at;
+        response.response.push_str(&chunk.response);
+        response.thinking.push_str(&chunk.thinking);
+        if chunk.done {
+            response.done = true;
+            response.done_reason = chunk.done_reason;
+            response.total_duration = chunk.total_duration;
+            response.load_duration = chunk.load_duration;
+            response.prompt_eval_count = chunk.prompt_eval_count;
+            response.prompt_eval_duration = chunk.prompt_eval_duration;
+            response.eval_count = chunk.eval_count;
+            response.eval_duration = chunk.eval_duration;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` once the final chunk of the response was pushed.
+    pub fn is_done(&self) -> bool {
+        self.response.done
+    }
+
+    /// Returns the response accumulated so far.
+    pub fn response(&self) -> &GenerateResponse {
+        &self.response
+    }
+
+    /// Returns the accumulated response, whether or not the final chunk was pushed.
+    pub fn into_response(self) -> GenerateResponse {
+        self.response
+    }
+
+    /// Returns the complete response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Protocol`] if the final chunk of the response was never pushed.
+    pub fn finish(self) -> Result<GenerateResponse> {
+        if !self.is_done() {
+            return Err(Error::Protocol(
+                "Generate stream ended before the final chunk".to_string(),
+            ));
+        }
+        Ok(self.response)
+    }
+}
+
+impl fmt::Debug for GenerateStreamAccumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenerateStreamAccumulator")
+            .field("response", &self.response)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,176 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::{Stream, StreamExt};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::{Error, Result};
+
+/// A stream item as it is shared between subscribers.
+type SharedItem<E> = std::result::Result<E, Arc<Error>>;
+
+/// A boxed source stream driven by a [`Broadcaster`].
+type Source<E> = Pin<Box<dyn Stream<Item = Result<E>> + Send>>;
+
+/// What a [`Broadcaster`] does when a subscriber's buffer is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Wait until the subscriber has room, slowing down the stream for all subscribers.
+    #[default]
+    Backpressure,
+    /// Drop the subscriber, which receives [`Error::SubscriberLagged`] after its buffered
+    /// events and then ends. The other subscribers are not slowed down.
+    DropSubscriber,
+}
+
+/// Configuration of a [`Broadcaster`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastConfig {
+    capacity: usize,
+    lag_policy: LagPolicy,
+}
+
+impl Default for BroadcastConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            lag_policy: LagPolicy::default(),
+        }
+    }
+}
+
+impl BroadcastConfig {
+    /// Creates a [`BroadcastConfig`] buffering 64 events per subscriber with
+    /// [`LagPolicy::Backpressure`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of events buffered for each subscriber. A capacity of 0 is raised to 1.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets what happens when a subscriber's buffer is full.
+    pub fn lag_policy(mut self, lag_policy: LagPolicy) -> Self {
+        self.lag_policy = lag_policy;
+        self
+    }
+}
+
+/// Fans out a single stream to several independent [`Subscriber`]s.
+///
+/// Every subscriber receives every event of the stream from the moment the broadcaster
+/// is started, with errors copied to each of them. The stream is driven by a background
+/// task. When the task finds that all subscribers were dropped, at the next event, it
+/// stops and drops the stream, which aborts its HTTP response.
+///
+/// Created with [`ChatStream::broadcast`](crate::types::chat::ChatStream::broadcast) or
+/// [`GenerateStream::broadcast`](crate::types::generate::GenerateStream::broadcast).
+pub struct Broadcaster<E> {
+    source: Source<E>,
+    config: BroadcastConfig,
+    senders: Vec<(mpsc::Sender<SharedItem<E>>, Arc<AtomicBool>)>,
+}
+
+impl<E> Broadcaster<E>
+where
+    E: Clone + Send + 'static,
+{
+    /// Creates a broadcaster for `source` without any subscribers.
+    pub fn new<S>(source: S, config: BroadcastConfig) -> Self
+    where
+        S: Stream<Item = Result<E>> + Send + 'static,
+    {
+        Self {
+            source: Box::pin(source),
+            config,
+            senders: Vec::new(),
+        }
+    }
+
+    /// Adds a subscriber, which receives the events of the stream once it is started.
+    pub fn subscribe(&mut self) -> Subscriber<E> {
+        let (sender, receiver) = mpsc::channel(self.config.capacity);
+        let lagged = Arc::new(AtomicBool::new(false));
+        self.senders.push((sender, lagged.clone()));
+        Subscriber {
+            receiver,
+            lagged,
+            capacity: self.config.capacity,
+        }
+    }
+
+    /// Starts driving the stream in a background task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn start(self) {
+        tokio::spawn(pump(self.source, self.senders, self.config.lag_policy));
+    }
+}
+
+/// Forwards the items of `source` to all open `senders`.
+async fn pump<E: Clone>(
+    mut source: Source<E>,
+    mut senders: Vec<(mpsc::Sender<SharedItem<E>>, Arc<AtomicBool>)>,
+    lag_policy: LagPolicy,
+) {
+    while !senders.is_empty() {
+        let Some(item) = source.next().await else {
+            break;
+        };
+        let item = item.map_err(Arc::new);
+        match lag_policy {
+            LagPolicy::Backpressure => {
+                let mut open = Vec::with_capacity(senders.len());
+                for (sender, lagged) in senders {
+                    if sender.send(item.clone()).await.is_ok() {
+                        open.push((sender, lagged));
+                    }
+                }
+                senders = open;
+            }
+            LagPolicy::DropSubscriber => {
+                senders.retain(|(sender, lagged)| match sender.try_send(item.clone()) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        lagged.store(true, Ordering::Release);
+                        false
+                    }
+                    Err(TrySendError::Closed(_)) => false,
+                });
+            }
+        }
+    }
+}
+
+/// A stream of the events of a [`Broadcaster`].
+pub struct Subscriber<E> {
+    receiver: mpsc::Receiver<SharedItem<E>>,
+    lagged: Arc<AtomicBool>,
+    capacity: usize,
+}
+
+impl<E> Stream for Subscriber<E> {
+    type Item = Result<E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match futures::ready!(this.receiver.poll_recv(cx)) {
+            Some(item) => Poll::Ready(Some(item.map_err(|error| {
+                Arc::try_unwrap(error).unwrap_or_else(|error| error.duplicate())
+            }))),
+            None if this.lagged.swap(false, Ordering::AcqRel) => {
+                Poll::Ready(Some(Err(Error::SubscriberLagged {
+                    capacity: this.capacity,
+                })))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{FutureExt, Stream};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+use crate::streaming::StreamChunk;
+use crate::{Error, Result};
+
+/// A stream that ends with [`Error::Cancelled`] as soon as its token is cancelled.
+///
+/// The wrapped stream is dropped on cancellation, which aborts the underlying HTTP
+/// response and releases any resources it holds.
+pub(crate) struct CancellableStream<S> {
+    inner: Option<S>,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+    chunks_received: usize,
+}
+
+impl<S> CancellableStream<S> {
+    pub(crate) fn new(inner: S, token: CancellationToken) -> Self {
+        Self {
+            inner: Some(inner),
+            cancelled: Box::pin(token.cancelled_owned()),
+            chunks_received: 0,
+        }
+    }
+}
+
+impl<S, E> Stream for CancellableStream<S>
+where
+    S: Stream<Item = Result<E>> + Unpin,
+    E: StreamChunk,
+{
+    type Item = Result<E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if this.cancelled.poll_unpin(cx).is_ready() {
+            this.inner = None;
+            return Poll::Ready(Some(Err(Error::Cancelled {
+                chunks_received: this.chunks_received,
+            })));
+        }
+
+        let item = futures::ready!(Pin::new(inner).poll_next(cx));
+        match &item {
+            Some(Ok(event)) if event.has_tokens() => this.chunks_received += 1,
+            Some(_) => {}
+            None => this.inner = None,
+        }
+        Poll::Ready(item)
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{HashSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+
+use crate::parser::StreamLineError;
+use crate::streaming::StreamTimings;
+use crate::types::chat::{ChatResponse, ChatStream, ChatStreamEvent, ToolCall};
+use crate::types::ResponseStats;
+use crate::Result;
+
+/// A higher-level event derived from the chunks of a [`ChatStream`].
+///
+/// Within a chunk, events are emitted in the order thinking, content, tool calls and
+/// completion. Empty deltas are never emitted.
+#[derive(Debug, Clone)]
+pub enum ChatDelta {
+    /// The model started thinking. Followed by one or more [`ChatDelta::ThinkingDelta`]s.
+    ThinkingStarted,
+    /// A piece of the model's thinking.
+    ThinkingDelta(String),
+    /// The model stopped thinking, because it started answering, called a tool or finished.
+    ThinkingFinished,
+    /// A piece of the answer content.
+    ContentDelta(String),
+    /// The model called a tool. Each tool call id is reported once.
+    ToolCallStarted(ToolCall),
+    /// The response is complete.
+    Done {
+        /// The timing and token statistics of the response.
+        stats: ResponseStats,
+        /// The reason why the response finished (e.g., "stop", "length").
+        done_reason: Option<String>,
+    },
+    /// The server reported an error in the stream.
+    Error(String),
+    /// A chunk could not be parsed; see [`ChatStreamEvent::Partial`].
+    Partial {
+        /// The un-parseable content.
+        partial: String,
+        /// An optional error message associated with the partial response.
+        error: Option<String>,
+    },
+    /// The server reported an error or a chunk could not be parsed; see
+    /// [`ChatStreamEvent::LineError`].
+    LineError(StreamLineError),
+}
+
+/// A stream of [`ChatDelta`]s, created with [`ChatStream::deltas`].
+pub struct ChatDeltaStream {
+    inner: ChatStream,
+    pending: VecDeque<ChatDelta>,
+    thinking: bool,
+    seen_tool_calls: HashSet<String>,
+}
+
+impl ChatDeltaStream {
+    pub(crate) fn new(inner: ChatStream) -> Self {
+        Self {
+            inner,
+            pending: VecDeque::new(),
+            thinking: false,
+            seen_tool_calls: HashSet::new(),
+        }
+    }
+
+    /// Returns the latency measurements of the underlying stream so far.
+    ///
+    /// See [`ChatStream::timings`].
+    pub fn timings(&self) -> Option<StreamTimings> {
+        self.inner.timings()
+    }
+
+    /// Queues the deltas of a single message chunk.
+    fn push_chunk(&mut self, chunk: ChatResponse) {
+        let stats = chunk.stats();
+        let message = chunk.message;
+        if !message.thinking.is_empty() {
+            if !self.thinking {
+                self.thinking = true;
+                self.pending.push_back(ChatDelta::ThinkingStarted);
+            }
+            self.pending
+                .push_back(ChatDelta::ThinkingDelta(message.thinking));
+        }
+
+        let answering = !message.content.is_empty() || !message.tool_calls.is_empty();
+        if self.thinking && (answering || chunk.done) {
+            self.thinking = false;
+            self.pending.push_back(ChatDelta::ThinkingFinished);
+        }
+        if !message.content.is_empty() {
+            self.pending
+                .push_back(ChatDelta::ContentDelta(message.content));
+        }
+        for call in message.tool_calls {
+            if self.seen_tool_calls.insert(call.id.clone()) {
+                self.pending.push_back(ChatDelta::ToolCallStarted(call));
+            }
+        }
+
+        if chunk.done {
+            self.pending.push_back(ChatDelta::Done {
+                stats,
+                done_reason: chunk.done_reason,
+            });
+        }
+    }
+}
+
+impl Stream for ChatDeltaStream {
+    type Item = Result<ChatDelta>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(delta) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(delta)));
+            }
+
+            let event = match futures::ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(Ok(event)) => event,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            };
+            match event {
+                ChatStreamEvent::Message(chunk) => this.push_chunk(chunk),
+                ChatStreamEvent::Error(error) => {
+                    return Poll::Ready(Some(Ok(ChatDelta::Error(error))))
+                }
+                ChatStreamEvent::Partial { partial, error } => {
+                    return Poll::Ready(Some(Ok(ChatDelta::Partial { partial, error })))
+                }
+                ChatStreamEvent::LineError(error) => {
+                    return Poll::Ready(Some(Ok(ChatDelta::LineError(error))))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+use crate::parser::{
+    GenericStreamParser, ParserConfig, StreamEventExt, StreamLineError, StreamLineErrorKind,
+};
+use crate::streaming::unparseable;
+use crate::{Error, Result};
+
+/// The version of the [`EventEnvelope`] format written by this crate.
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// A stream event as it is stored in an NDJSON recording.
+///
+/// Each line of a recording is one envelope, with the fields of the event flattened
+/// into it:
+///
+/// ```json
+/// {"version":1,"received_at":1700000000123,"kind":"message","payload":{"model":"llama3", ...}}
+/// {"version":1,"received_at":1700000000456,"kind":"error","payload":"model crashed"}
+/// {"version":1,"received_at":1700000000789,"kind":"partial","payload":{"partial":"{\"mod","error":null}}
+/// ```
+///
+/// - `version` is [`ENVELOPE_VERSION`], and is increased whenever the format changes in
+///   a way older readers cannot handle.
+/// - `received_at` is when the event was taken from the stream, in milliseconds since
+///   the Unix epoch.
+/// - `kind` is `message`, `error` or `partial`, for both
+///   [`ChatStreamEvent`](crate::types::chat::ChatStreamEvent) and
+///   [`GenerateStreamEvent`](crate::types::generate::GenerateStreamEvent).
+/// - `payload` is the response chunk for `message`, the error text for `error`, and the
+///   un-parseable text with the reason it could not be parsed for `partial`.
+///
+/// Recordings are written with
+/// [`ChatStream::write_ndjson`](crate::types::chat::ChatStream::write_ndjson) and read
+/// back with [`ChatStream::replay`](crate::types::chat::ChatStream::replay), or the
+/// same methods of [`GenerateStream`](crate::types::generate::GenerateStream).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<E> {
+    /// The version of the envelope format.
+    pub version: u32,
+    /// When the event was received, in milliseconds since the Unix epoch.
+    pub received_at: u64,
+    /// The event, serialized as its `kind` and `payload`.
+    #[serde(flatten)]
+    pub event: E,
+}
+
+impl<E> EventEnvelope<E> {
+    /// Wraps an event that was just received.
+    pub fn new(event: E) -> Self {
+        let received_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Self {
+            version: ENVELOPE_VERSION,
+            received_at,
+            event,
+        }
+    }
+
+    /// Returns when the event was received.
+    pub fn received_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.received_at)
+    }
+}
+
+/// A line of a recording, turned into the event or the error it stands for.
+enum Replayed<E> {
+    Event(E),
+    Failed(Error),
+}
+
+impl<E> StreamEventExt<EventEnvelope<E>> for Replayed<E> {
+    fn from_message(envelope: EventEnvelope<E>) -> Self {
+        if envelope.version > ENVELOPE_VERSION {
+            return Replayed::Failed(Error::Protocol(format!(
+                "Unsupported event envelope version {}",
+                envelope.version
+            )));
+        }
+        Replayed::Event(envelope.event)
+    }
+
+    fn from_error(err: String) -> Self {
+        Replayed::Failed(Error::Server(err))
+    }
+
+    fn partial(partial: String, _error: Option<String>) -> Self {
+        Replayed::Failed(unparseable(partial))
+    }
+
+    fn from_line_error(error: StreamLineError) -> Self {
+        match error.kind() {
+            StreamLineErrorKind::Server => Self::from_error(error.message().to_string()),
+            _ => Replayed::Failed(Error::MalformedLine(Box::new(error))),
+        }
+    }
+}
+
+/// Reads the events of an NDJSON recording of [`EventEnvelope`]s.
+///
+/// The stream ends with an [`Error::MalformedLine`] at the first line that is not an
+/// envelope of `E`.
+pub(crate) fn replay<S, E>(recording: S) -> impl Stream<Item = Result<E>> + Send + 'static
+where
+    S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
+    E: DeserializeOwned + Send + Unpin + 'static,
+{
+    let config = ParserConfig::new().strict(true);
+    GenericStreamParser::<S, EventEnvelope<E>, Replayed<E>>::with_config(recording, config).map(
+        |line| match line? {
+            Replayed::Event(event) => Ok(event),
+            Replayed::Failed(error) => Err(error),
+        },
+    )
+}
+
+/// Reads the chunks of a recording from `reader`.
+pub(crate) fn read_recording<R>(
+    reader: R,
+) -> impl Stream<Item = Result<Bytes>> + Send + Unpin + 'static
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    ReaderStream::new(reader).map_err(Error::Io)
+}
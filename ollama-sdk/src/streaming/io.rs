@@ -0,0 +1,109 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio_util::io::StreamReader;
+
+use crate::streaming::EventEnvelope;
+use crate::Result;
+
+/// The boxed chunks of text read by a [`TextReader`].
+type TextChunks = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
+
+/// An [`AsyncRead`] over the generated content of a stream, as UTF-8 text.
+///
+/// Thinking, tool calls and statistics are left out. Errors of the stream, including
+/// error events and un-parseable chunks, are returned as [`io::Error`]s wrapping the
+/// [`Error`](crate::Error) that [`ChatStream::collect_response`](crate::types::chat::ChatStream::collect_response)
+/// would return.
+///
+/// Created with [`ChatStream::into_text_reader`](crate::types::chat::ChatStream::into_text_reader)
+/// or [`GenerateStream::into_text_reader`](crate::types::generate::GenerateStream::into_text_reader).
+pub struct TextReader {
+    inner: StreamReader<TextChunks, Bytes>,
+}
+
+impl TextReader {
+    /// Creates a reader over the pieces of text of `stream`.
+    pub(crate) fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<String>> + Send + 'static,
+    {
+        let chunks = stream.filter_map(|text| async move {
+            match text {
+                Ok(text) if text.is_empty() => None,
+                Ok(text) => Some(Ok(Bytes::from(text))),
+                Err(error) => Some(Err(io::Error::other(error))),
+            }
+        });
+        Self {
+            inner: StreamReader::new(Box::pin(chunks)),
+        }
+    }
+}
+
+impl AsyncRead for TextReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncBufRead for TextReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.get_mut().inner).consume(amt)
+    }
+}
+
+/// Writes the events of `stream` to `writer` as NDJSON, one [`EventEnvelope`] per line,
+/// and returns the number of events written.
+///
+/// Error events and un-parseable chunks are written like any other event. The writer is
+/// flushed once the stream ends.
+///
+/// # Arguments
+///
+/// * `stream` - The stream of events to archive.
+/// * `writer` - Where the NDJSON is written to.
+///
+/// # Errors
+///
+/// Returns the first error of the stream, after flushing the events before it, an
+/// [`Error::JsonParse`](crate::Error::JsonParse) if an event cannot be serialized, or an
+/// [`Error::Io`](crate::Error::Io) if writing fails.
+pub async fn write_ndjson<S, E, W>(mut stream: S, writer: &mut W) -> Result<u64>
+where
+    S: Stream<Item = Result<E>> + Unpin,
+    E: Serialize,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut written = 0;
+    let mut line = Vec::new();
+    while let Some(event) = stream.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(error) => {
+                writer.flush().await?;
+                return Err(error);
+            }
+        };
+        line.clear();
+        serde_json::to_writer(&mut line, &EventEnvelope::new(event))?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+        written += 1;
+    }
+    writer.flush().await?;
+    Ok(written)
+}
@@ -0,0 +1,74 @@
+//! Adapters and helpers for working with streamed chat and generate responses.
+//!
+//! The adapters in this module are generic over the events of a stream through the
+//! [`StreamChunk`] trait, which is implemented for both
+//! [`ChatStreamEvent`](crate::types::chat::ChatStreamEvent) and
+//! [`GenerateStreamEvent`](crate::types::generate::GenerateStreamEvent).
+
+mod accumulate;
+mod broadcast;
+mod cancel;
+mod deltas;
+mod envelope;
+mod io;
+mod partial_json;
+mod stop;
+mod timings;
+
+pub use accumulate::{ChatStreamAccumulator, GenerateStreamAccumulator};
+pub use broadcast::{BroadcastConfig, Broadcaster, LagPolicy, Subscriber};
+pub(crate) use cancel::CancellableStream;
+pub use deltas::{ChatDelta, ChatDeltaStream};
+pub(crate) use envelope::{read_recording, replay};
+pub use envelope::{EventEnvelope, ENVELOPE_VERSION};
+pub use io::{write_ndjson, TextReader};
+pub use partial_json::{PartialJson, PartialJsonParser, PartialJsonStream};
+pub use stop::{StopConditions, StopReason, StopStream};
+pub(crate) use timings::StreamTimer;
+pub use timings::StreamTimings;
+
+use crate::parser::StreamLineError;
+use crate::Error;
+
+/// The error for a partial (un-parseable) event consumed as part of a response.
+pub(crate) fn unparseable(partial: String) -> Error {
+    Error::Protocol(format!("Un-parseable stream chunk: {}", partial))
+}
+
+/// The error for a line error event consumed as part of a response: an
+/// [`Error::Server`] for an error sent by the server, and an [`Error::MalformedLine`]
+/// otherwise.
+pub(crate) fn line_failure(error: StreamLineError) -> Error {
+    if error.is_server_error() {
+        Error::Server(error.message().to_string())
+    } else {
+        Error::MalformedLine(Box::new(error))
+    }
+}
+
+/// Access to the model output carried by a stream event.
+pub trait StreamChunk {
+    /// Returns the content text of the event, if it is a message chunk.
+    fn content(&self) -> Option<&str>;
+
+    /// Returns the content text of the event for modification, if it is a message chunk.
+    fn content_mut(&mut self) -> Option<&mut String>;
+
+    /// Returns the thinking text of the event, if it is a message chunk.
+    fn thinking(&self) -> Option<&str>;
+
+    /// Returns `true` if the event is the final message chunk of the response.
+    fn is_done(&self) -> bool;
+
+    /// Returns a message chunk of the same response that carries only `content`, or
+    /// `None` if the event is not a message chunk.
+    fn with_content(&self, content: String) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Returns `true` if the event carries generated tokens, i.e. non-empty content or thinking.
+    fn has_tokens(&self) -> bool {
+        self.content().is_some_and(|content| !content.is_empty())
+            || self.thinking().is_some_and(|thinking| !thinking.is_empty())
+    }
+}
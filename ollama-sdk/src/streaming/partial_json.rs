@@ -0,0 +1,478 @@
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+/// The boxed pieces of content text parsed by a [`PartialJsonStream`].
+type ContentStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Key,
+    Colon,
+    Value,
+    CommaOrEnd,
+}
+
+/// An open object or array.
+#[derive(Debug, Clone)]
+struct Frame {
+    expect: Expect,
+    /// The object or array with the members completed so far.
+    value: Value,
+    /// The key of the object member whose value is being scanned.
+    key: Option<String>,
+}
+
+/// The token that is being scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    None,
+    String { key: bool },
+    Scalar { start: usize },
+}
+
+/// An incomplete escape sequence inside a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    None,
+    Backslash,
+    Unicode { digits: u8, value: u32 },
+}
+
+/// A tolerant, incremental parser for a JSON document that arrives in pieces.
+///
+/// Pieces of text are scanned once as they are pushed, and the value is built up as its
+/// members complete. At any point, [`PartialJsonParser::snapshot`] returns the value of
+/// the text so far, with open strings, arrays and objects closed and incomplete members
+/// left out: an object member appears once its key is complete and its value has
+/// started, a string grows as it arrives, and a number or literal appears once it is
+/// followed by the next token. Each snapshot is therefore a prefix of the final value.
+///
+/// Taking a snapshot does not parse the text again, but clones the value built so far.
+#[derive(Debug, Clone)]
+pub struct PartialJsonParser {
+    text: String,
+    frames: Vec<Frame>,
+    token: Token,
+    /// The decoded text of the string being scanned, up to `raw_start`.
+    string: String,
+    /// Where the text of the string being scanned that is not decoded yet starts.
+    raw_start: usize,
+    escape: Escape,
+    /// A high surrogate escape that still needs its low surrogate.
+    high_surrogate: Option<u32>,
+    root: Option<Value>,
+    root_started: bool,
+    failed: bool,
+    /// Increased whenever the value of a snapshot changes.
+    revision: u64,
+}
+
+impl Default for PartialJsonParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialJsonParser {
+    /// Creates a parser for an empty document.
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            frames: Vec::new(),
+            token: Token::None,
+            string: String::new(),
+            raw_start: 0,
+            escape: Escape::None,
+            high_surrogate: None,
+            root: None,
+            root_started: false,
+            failed: false,
+            revision: 0,
+        }
+    }
+
+    /// Adds the next piece of the document.
+    pub fn push(&mut self, text: &str) {
+        let offset = self.text.len();
+        self.text.push_str(text);
+        for (i, byte) in text.bytes().enumerate() {
+            if self.failed {
+                return;
+            }
+            self.scan(offset + i, byte);
+        }
+    }
+
+    /// Returns the document text pushed so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns `true` once the root value of the document is complete.
+    pub fn is_complete(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Returns `true` if the text so far cannot be the start of a JSON document.
+    pub fn is_invalid(&self) -> bool {
+        self.failed
+    }
+
+    /// Returns the value of the document so far, or `None` if nothing of the value is
+    /// known yet or the text is not valid JSON.
+    pub fn snapshot(&self) -> Option<Value> {
+        if self.failed {
+            return None;
+        }
+        if let Some(root) = &self.root {
+            return Some(root.clone());
+        }
+
+        let mut value = match self.token {
+            Token::String { key: false } => {
+                let mut string = self.string.clone();
+                if self.escape == Escape::None {
+                    string.push_str(&self.text[self.raw_start..]);
+                }
+                Some(Value::String(string))
+            }
+            _ => None,
+        };
+        for frame in self.frames.iter().rev() {
+            let mut container = frame.value.clone();
+            if let Some(value) = value {
+                insert(&mut container, frame.key.clone(), value);
+            }
+            value = Some(container);
+        }
+        value
+    }
+
+    /// Parses the whole document as a `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::JsonParse`](crate::Error::JsonParse) if the document is
+    /// incomplete, invalid or does not match `T`.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.text)?)
+    }
+
+    fn scan(&mut self, i: usize, byte: u8) {
+        match self.token {
+            Token::String { key } => self.scan_string(i, byte, key),
+            Token::Scalar { .. } if is_scalar_byte(byte) => {}
+            Token::Scalar { start } => {
+                self.token = Token::None;
+                match serde_json::from_str(&self.text[start..i]) {
+                    Ok(value) => {
+                        self.finish_value(value);
+                        self.revision += 1;
+                        self.scan_structure(i, byte);
+                    }
+                    Err(_) => self.failed = true,
+                }
+            }
+            Token::None => self.scan_structure(i, byte),
+        }
+    }
+
+    fn scan_string(&mut self, i: usize, byte: u8, key: bool) {
+        match self.escape {
+            Escape::Backslash => {
+                let unescaped = match byte {
+                    b'u' => {
+                        self.escape = Escape::Unicode {
+                            digits: 0,
+                            value: 0,
+                        };
+                        return;
+                    }
+                    b'"' => '"',
+                    b'\\' => '\\',
+                    b'/' => '/',
+                    b'b' => '\u{8}',
+                    b'f' => '\u{c}',
+                    b'n' => '\n',
+                    b'r' => '\r',
+                    b't' => '\t',
+                    _ => {
+                        self.failed = true;
+                        return;
+                    }
+                };
+                self.escape = Escape::None;
+                self.raw_start = i + 1;
+                if self.high_surrogate.is_some() {
+                    self.failed = true;
+                    return;
+                }
+                self.push_char(Some(unescaped), key);
+            }
+            Escape::Unicode { digits, value } => {
+                let Some(digit) = (byte as char).to_digit(16) else {
+                    self.failed = true;
+                    return;
+                };
+                let value = value * 16 + digit;
+                if digits < 3 {
+                    self.escape = Escape::Unicode {
+                        digits: digits + 1,
+                        value,
+                    };
+                    return;
+                }
+                self.escape = Escape::None;
+                self.raw_start = i + 1;
+                match (self.high_surrogate.take(), value) {
+                    (None, 0xD800..=0xDBFF) => self.high_surrogate = Some(value),
+                    (Some(high), 0xDC00..=0xDFFF) => {
+                        let c = 0x10000 + ((high - 0xD800) << 10) + (value - 0xDC00);
+                        self.push_char(char::from_u32(c), key);
+                    }
+                    (Some(_), _) | (None, 0xDC00..=0xDFFF) => self.failed = true,
+                    (None, value) => self.push_char(char::from_u32(value), key),
+                }
+            }
+            // A high surrogate must be followed by the escape of a low surrogate.
+            Escape::None if self.high_surrogate.is_some() && byte != b'\\' => {
+                self.failed = true;
+            }
+            Escape::None => match byte {
+                b'\\' => {
+                    self.decode_string(i);
+                    self.escape = Escape::Backslash;
+                }
+                b'"' => {
+                    self.decode_string(i);
+                    self.token = Token::None;
+                    let string = std::mem::take(&mut self.string);
+                    if key {
+                        let frame = self.top();
+                        frame.key = Some(string);
+                        frame.expect = Expect::Colon;
+                    } else {
+                        self.finish_value(Value::String(string));
+                    }
+                }
+                _ if !key => self.revision += 1,
+                _ => {}
+            },
+        }
+    }
+
+    /// Appends the text of the string being scanned up to `end` to the decoded string.
+    fn decode_string(&mut self, end: usize) {
+        self.string.push_str(&self.text[self.raw_start..end]);
+        self.raw_start = end;
+    }
+
+    /// Appends a character decoded from an escape to the string being scanned.
+    fn push_char(&mut self, c: Option<char>, key: bool) {
+        match c {
+            Some(c) => {
+                self.string.push(c);
+                if !key {
+                    self.revision += 1;
+                }
+            }
+            None => self.failed = true,
+        }
+    }
+
+    fn scan_structure(&mut self, i: usize, byte: u8) {
+        let expect = self.frames.last().map(|frame| frame.expect);
+        match byte {
+            b' ' | b'\t' | b'\n' | b'\r' => {}
+            b'{' | b'[' if self.start_value() => {
+                let (value, expect) = if byte == b'{' {
+                    (Value::Object(Map::new()), Expect::Key)
+                } else {
+                    (Value::Array(Vec::new()), Expect::Value)
+                };
+                self.frames.push(Frame {
+                    expect,
+                    value,
+                    key: None,
+                });
+                self.revision += 1;
+            }
+            b'}' | b']' => match self.frames.pop() {
+                Some(frame)
+                    if frame.value.is_object() == (byte == b'}')
+                        && matches!(
+                            frame.expect,
+                            Expect::CommaOrEnd | Expect::Key | Expect::Value
+                        ) =>
+                {
+                    self.finish_value(frame.value)
+                }
+                _ => self.failed = true,
+            },
+            b',' if expect == Some(Expect::CommaOrEnd) => {
+                let frame = self.top();
+                frame.expect = if frame.value.is_object() {
+                    Expect::Key
+                } else {
+                    Expect::Value
+                };
+            }
+            b':' if expect == Some(Expect::Colon) => self.top().expect = Expect::Value,
+            b'"' if expect == Some(Expect::Key) => {
+                self.top().expect = Expect::Colon;
+                self.start_string(i, true);
+            }
+            b'"' if self.start_value() => {
+                self.start_string(i, false);
+                self.revision += 1;
+            }
+            _ if is_scalar_byte(byte) && self.start_value() => {
+                self.token = Token::Scalar { start: i }
+            }
+            _ => self.failed = true,
+        }
+    }
+
+    /// Starts scanning the string whose opening quote is at `i`.
+    fn start_string(&mut self, i: usize, key: bool) {
+        self.token = Token::String { key };
+        self.string.clear();
+        self.raw_start = i + 1;
+    }
+
+    /// Marks the start of a value, returning `false` if no value is expected.
+    fn start_value(&mut self) -> bool {
+        match self.frames.last_mut() {
+            Some(frame) if frame.expect == Expect::Value => {
+                frame.expect = Expect::CommaOrEnd;
+                true
+            }
+            Some(_) => false,
+            None if self.root_started => false,
+            None => {
+                self.root_started = true;
+                true
+            }
+        }
+    }
+
+    /// Adds a complete value to the open container, or makes it the root value.
+    fn finish_value(&mut self, value: Value) {
+        match self.frames.last_mut() {
+            Some(frame) => {
+                let key = frame.key.take();
+                insert(&mut frame.value, key, value);
+            }
+            None => self.root = Some(value),
+        }
+    }
+
+    fn top(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("members are only scanned inside a container")
+    }
+}
+
+/// Adds `value` to the object or array `container`, under `key` for objects.
+fn insert(container: &mut Value, key: Option<String>, value: Value) {
+    match container {
+        Value::Object(map) => {
+            if let Some(key) = key {
+                map.insert(key, value);
+            }
+        }
+        Value::Array(items) => items.push(value),
+        _ => {}
+    }
+}
+
+/// Returns `true` for the bytes of numbers and of the literals `true`, `false` and `null`.
+fn is_scalar_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'+' | b'.')
+}
+
+/// An item of a [`PartialJsonStream`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartialJson<T> {
+    /// The value of the content so far, whenever it changed.
+    Snapshot(Value),
+    /// The complete content parsed as a `T`, which is the last item of the stream.
+    Complete(T),
+}
+
+/// A stream of progressively more complete values of JSON content.
+///
+/// The content of the wrapped stream is fed into a [`PartialJsonParser`], and a
+/// [`PartialJson::Snapshot`] is returned whenever the value changes. Once the stream
+/// ends, the whole content is parsed and returned as [`PartialJson::Complete`].
+///
+/// Created with [`ChatStream::partial_json`](crate::types::chat::ChatStream::partial_json)
+/// or [`GenerateStream::partial_json`](crate::types::generate::GenerateStream::partial_json).
+pub struct PartialJsonStream<T> {
+    inner: Option<ContentStream>,
+    parser: PartialJsonParser,
+    /// The revision of the parser when the last snapshot was returned.
+    revision: u64,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<T> PartialJsonStream<T> {
+    /// Creates a stream parsing the pieces of content of `content`.
+    pub(crate) fn new<S>(content: S) -> Self
+    where
+        S: Stream<Item = Result<String>> + Send + 'static,
+    {
+        Self {
+            inner: Some(Box::pin(content)),
+            parser: PartialJsonParser::new(),
+            revision: 0,
+            _target: PhantomData,
+        }
+    }
+
+    /// Returns the parser with the content received so far.
+    pub fn parser(&self) -> &PartialJsonParser {
+        &self.parser
+    }
+}
+
+impl<T: DeserializeOwned> Stream for PartialJsonStream<T> {
+    type Item = Result<PartialJson<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let Some(inner) = this.inner.as_mut() else {
+                return Poll::Ready(None);
+            };
+            match futures::ready!(inner.as_mut().poll_next(cx)) {
+                Some(Ok(text)) => {
+                    this.parser.push(&text);
+                    if this.parser.revision == this.revision {
+                        continue;
+                    }
+                    this.revision = this.parser.revision;
+                    if let Some(value) = this.parser.snapshot() {
+                        return Poll::Ready(Some(Ok(PartialJson::Snapshot(value))));
+                    }
+                }
+                Some(Err(error)) => {
+                    this.inner = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+                None => {
+                    this.inner = None;
+                    return Poll::Ready(Some(this.parser.parse().map(PartialJson::Complete)));
+                }
+            }
+        }
+    }
+}
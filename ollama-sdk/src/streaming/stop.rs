@@ -0,0 +1,221 @@
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+
+use crate::streaming::StreamChunk;
+use crate::Result;
+
+/// The conditions under which a [`StopStream`] ends a stream on the client side.
+///
+/// Stop sequences are matched against the content text of the stream, also when a
+/// sequence is split across chunks. The content is truncated right before the match.
+#[derive(Debug, Clone, Default)]
+pub struct StopConditions {
+    sequences: Vec<String>,
+    max_chars: Option<usize>,
+}
+
+impl StopConditions {
+    /// Creates empty [`StopConditions`], which never stop a stream.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a stop sequence. Empty sequences are ignored.
+    pub fn sequence(mut self, sequence: impl Into<String>) -> Self {
+        let sequence = sequence.into();
+        if !sequence.is_empty() {
+            self.sequences.push(sequence);
+        }
+        self
+    }
+
+    /// Adds several stop sequences. Empty sequences are ignored.
+    pub fn sequences<I>(self, sequences: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        sequences.into_iter().fold(self, Self::sequence)
+    }
+
+    /// Sets the maximum number of characters of content before the stream is stopped.
+    pub fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Returns the byte index and the sequence of the earliest stop sequence in `text`.
+    fn find<'a>(&'a self, text: &str) -> Option<(usize, &'a str)> {
+        self.sequences
+            .iter()
+            .filter_map(|sequence| Some((text.find(sequence.as_str())?, sequence.as_str())))
+            .min_by_key(|(index, _)| *index)
+    }
+
+    /// Returns the length of the longest suffix of `text` that starts a stop sequence.
+    fn partial_match(&self, text: &str) -> usize {
+        self.sequences
+            .iter()
+            .flat_map(|sequence| {
+                (1..sequence.len())
+                    .rev()
+                    .filter(|&len| sequence.is_char_boundary(len))
+                    .find(|&len| text.ends_with(&sequence[..len]))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The reason why a [`StopStream`] stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The content contained the stop sequence.
+    Sequence(String),
+    /// The content reached the maximum number of characters.
+    MaxLength(usize),
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Sequence(sequence) => write!(f, "stop sequence {:?}", sequence),
+            StopReason::MaxLength(max) => write!(f, "maximum length of {} characters", max),
+        }
+    }
+}
+
+/// A stream that ends as soon as its content matches one of its [`StopConditions`].
+///
+/// Content that could be the start of a stop sequence is held back until the next chunk
+/// shows whether the sequence completes; it is prepended to that chunk's content. If the
+/// wrapped stream fails or ends without a final chunk instead, the held content is
+/// returned as a chunk of its own before the error or the end of the stream. When a
+/// condition is met, the chunk is truncated and returned, and the wrapped stream is
+/// dropped, which aborts the HTTP response so the server stops generating. The stream
+/// then ends without the server's final chunk, and [`StopStream::stop_reason`] reports
+/// which condition fired.
+///
+/// Created with [`ChatStream::stop_on`](crate::types::chat::ChatStream::stop_on) or
+/// [`GenerateStream::stop_on`](crate::types::generate::GenerateStream::stop_on).
+pub struct StopStream<S, E> {
+    inner: Option<S>,
+    conditions: StopConditions,
+    held: String,
+    /// A chunk of the response the held content came from, to return it in.
+    held_chunk: Option<E>,
+    /// An item of the wrapped stream to return after the held content.
+    queued: Option<Result<E>>,
+    emitted_chars: usize,
+    stop_reason: Option<StopReason>,
+}
+
+impl<S, E: StreamChunk> StopStream<S, E> {
+    /// Wraps `inner`, stopping it when one of `conditions` is met.
+    pub fn new(inner: S, conditions: StopConditions) -> Self {
+        Self {
+            inner: Some(inner),
+            conditions,
+            held: String::new(),
+            held_chunk: None,
+            queued: None,
+            emitted_chars: 0,
+            stop_reason: None,
+        }
+    }
+
+    /// Returns the condition that stopped the stream, if any.
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
+    }
+
+    /// Replaces `content` with the part of the held and new content that can be emitted,
+    /// returning the reason if a stop condition was met.
+    fn truncate(&mut self, content: &mut String, done: bool) -> Option<StopReason> {
+        let mut text = std::mem::take(&mut self.held);
+        text.push_str(content);
+
+        let mut end = text.len();
+        let mut reason = None;
+        if let Some((index, sequence)) = self.conditions.find(&text) {
+            end = index;
+            reason = Some(StopReason::Sequence(sequence.to_string()));
+        } else if !done {
+            end -= self.conditions.partial_match(&text);
+            self.held = text[end..].to_string();
+        }
+
+        if let Some(max) = self.conditions.max_chars {
+            let remaining = max.saturating_sub(self.emitted_chars);
+            if let Some((index, _)) = text[..end].char_indices().nth(remaining) {
+                end = index;
+                reason = Some(StopReason::MaxLength(max));
+                self.held.clear();
+            }
+        }
+
+        self.emitted_chars += text[..end].chars().count();
+        text.truncate(end);
+        *content = text;
+        reason
+    }
+
+    /// Returns the held content as a chunk of its own, if there is any.
+    fn flush(&mut self) -> Option<E> {
+        let mut chunk = self.held_chunk.take()?;
+        let content = chunk.content_mut()?;
+        if let Some(reason) = self.truncate(content, true) {
+            self.stop_reason = Some(reason);
+            self.inner = None;
+        }
+        (!content.is_empty()).then_some(chunk)
+    }
+}
+
+impl<S, E> Stream for StopStream<S, E>
+where
+    S: Stream<Item = Result<E>> + Unpin,
+    E: StreamChunk + Unpin,
+{
+    type Item = Result<E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(item) = this.queued.take() {
+            return Poll::Ready(Some(item));
+        }
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        match futures::ready!(Pin::new(inner).poll_next(cx)) {
+            Some(Ok(mut event)) => {
+                let done = event.is_done();
+                if let Some(content) = event.content_mut() {
+                    if let Some(reason) = this.truncate(content, done) {
+                        this.stop_reason = Some(reason);
+                        this.inner = None;
+                    }
+                }
+                if !this.held.is_empty() {
+                    this.held_chunk = event.with_content(String::new());
+                }
+                Poll::Ready(Some(Ok(event)))
+            }
+            Some(Err(error)) => match this.flush() {
+                Some(chunk) => {
+                    this.queued = Some(Err(error));
+                    Poll::Ready(Some(Ok(chunk)))
+                }
+                None => Poll::Ready(Some(Err(error))),
+            },
+            None => {
+                this.inner = None;
+                Poll::Ready(this.flush().map(Ok))
+            }
+        }
+    }
+}
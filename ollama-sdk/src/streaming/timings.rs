@@ -0,0 +1,221 @@
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+#[cfg(feature = "metrics")]
+use metrics::histogram;
+
+use crate::streaming::StreamChunk;
+use crate::Result;
+
+/// Latency measurements of a single streamed response.
+///
+/// Streams returned by the client record when the request was sent, when the first byte
+/// of the response body and the first generated token arrived, and when the stream
+/// completed. Tokens are counted per chunk, since the server sends one token per chunk.
+///
+/// With the `metrics` feature enabled, the durations are also recorded as histograms
+/// labeled by model:
+///
+/// * `ollama_client.stream_inter_token_latency_seconds`, for every gap between two chunks
+///   with tokens as it happens
+/// * `ollama_client.stream_time_to_first_byte_seconds`,
+///   `ollama_client.stream_time_to_first_token_seconds` and
+///   `ollama_client.stream_duration_seconds`, once the stream completes or is dropped
+///   before completing
+///
+/// Requests that fail before the response starts, e.g. with an error status or a refused
+/// connection, record no histograms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTimings {
+    request_sent: Instant,
+    first_byte: Option<Instant>,
+    first_token: Option<Instant>,
+    last_token: Option<Instant>,
+    completed: Option<Instant>,
+    token_chunks: u64,
+}
+
+impl StreamTimings {
+    /// Returns when the request was sent.
+    pub fn request_sent(&self) -> Instant {
+        self.request_sent
+    }
+
+    /// Returns when the first byte of the response body arrived.
+    pub fn first_byte(&self) -> Option<Instant> {
+        self.first_byte
+    }
+
+    /// Returns when the first chunk with generated content or thinking arrived.
+    pub fn first_token(&self) -> Option<Instant> {
+        self.first_token
+    }
+
+    /// Returns when the stream completed, either with its final chunk, an error or its end.
+    pub fn completed(&self) -> Option<Instant> {
+        self.completed
+    }
+
+    /// Returns the number of chunks that carried generated tokens.
+    pub fn token_chunks(&self) -> u64 {
+        self.token_chunks
+    }
+
+    /// Returns the time from sending the request to the first byte of the response body.
+    pub fn time_to_first_byte(&self) -> Option<Duration> {
+        self.first_byte.map(|at| at - self.request_sent)
+    }
+
+    /// Returns the time from sending the request to the first generated token.
+    pub fn time_to_first_token(&self) -> Option<Duration> {
+        self.first_token.map(|at| at - self.request_sent)
+    }
+
+    /// Returns the mean time between two consecutive generated tokens.
+    ///
+    /// Returns `None` until at least two chunks with tokens were received.
+    pub fn mean_inter_token_latency(&self) -> Option<Duration> {
+        let (first, last) = self.first_token.zip(self.last_token)?;
+        let gaps = u32::try_from(self.token_chunks.checked_sub(1)?).ok()?;
+        (gaps > 0).then(|| (last - first) / gaps)
+    }
+
+    /// Returns the time from sending the request to the completion of the stream.
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.completed.map(|at| at - self.request_sent)
+    }
+}
+
+/// Records the [`StreamTimings`] of a stream as its items are observed.
+pub(crate) struct StreamTimer {
+    timings: StreamTimings,
+    first_byte: Arc<OnceLock<Instant>>,
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    model: String,
+    /// Whether the response started, so that the timings are worth recording.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    armed: bool,
+}
+
+impl StreamTimer {
+    /// Starts timing a request for `model` that is about to be sent.
+    pub(crate) fn start(model: &str) -> Self {
+        Self {
+            timings: StreamTimings {
+                request_sent: Instant::now(),
+                first_byte: None,
+                first_token: None,
+                last_token: None,
+                completed: None,
+                token_chunks: 0,
+            },
+            first_byte: Arc::new(OnceLock::new()),
+            model: model.to_string(),
+            armed: false,
+        }
+    }
+
+    /// Wraps the response body so that the arrival of its first byte is recorded.
+    ///
+    /// The timings are only recorded as metrics once the timer has a response body.
+    pub(crate) fn body<S>(&mut self, body: S) -> FirstByteStream<S> {
+        self.armed = true;
+        FirstByteStream {
+            inner: body,
+            first_byte: self.first_byte.clone(),
+        }
+    }
+
+    /// Records an item returned by the timed stream.
+    pub(crate) fn observe<E: StreamChunk>(&mut self, item: Option<&Result<E>>) {
+        if self.timings.completed.is_some() {
+            return;
+        }
+        let now = Instant::now();
+        match item {
+            Some(Ok(event)) => {
+                if event.has_tokens() {
+                    #[cfg(feature = "metrics")]
+                    if let Some(last_token) = self.timings.last_token {
+                        histogram!("ollama_client.stream_inter_token_latency_seconds", "model" => self.model.clone())
+                            .record((now - last_token).as_secs_f64());
+                    }
+                    self.timings.first_token.get_or_insert(now);
+                    self.timings.last_token = Some(now);
+                    self.timings.token_chunks += 1;
+                }
+                if event.is_done() {
+                    self.complete(now);
+                }
+            }
+            Some(Err(_)) | None => self.complete(now),
+        }
+    }
+
+    /// Returns the timings recorded so far.
+    pub(crate) fn timings(&self) -> StreamTimings {
+        StreamTimings {
+            first_byte: self.first_byte.get().copied(),
+            ..self.timings
+        }
+    }
+
+    fn complete(&mut self, now: Instant) {
+        self.timings.completed = Some(now);
+
+        #[cfg(feature = "metrics")]
+        if self.armed {
+            let timings = self.timings();
+            if let Some(duration) = timings.time_to_first_byte() {
+                histogram!("ollama_client.stream_time_to_first_byte_seconds", "model" => self.model.clone())
+                    .record(duration.as_secs_f64());
+            }
+            if let Some(duration) = timings.time_to_first_token() {
+                histogram!("ollama_client.stream_time_to_first_token_seconds", "model" => self.model.clone())
+                    .record(duration.as_secs_f64());
+            }
+            if let Some(duration) = timings.total_duration() {
+                histogram!("ollama_client.stream_duration_seconds", "model" => self.model.clone())
+                    .record(duration.as_secs_f64());
+            }
+        }
+    }
+}
+
+impl Drop for StreamTimer {
+    /// Completes the timings of a stream that is dropped before its end, e.g. after a
+    /// stop sequence or a cancellation.
+    fn drop(&mut self) {
+        if self.timings.completed.is_none() {
+            self.complete(Instant::now());
+        }
+    }
+}
+
+/// A response body that records when its first non-empty chunk arrives.
+pub(crate) struct FirstByteStream<S> {
+    inner: S,
+    first_byte: Arc<OnceLock<Instant>>,
+}
+
+impl<S, B> Stream for FirstByteStream<S>
+where
+    S: Stream<Item = Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let item = futures::ready!(Pin::new(&mut this.inner).poll_next(cx));
+        if let Some(Ok(chunk)) = &item {
+            if !chunk.as_ref().is_empty() {
+                let _ = this.first_byte.set(Instant::now());
+            }
+        }
+        Poll::Ready(item)
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use bytes::Bytes;
+use http::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::types::HttpRequest;
+use crate::{Error, Result};
+
+/// A recorded set of HTTP interactions, as written by
+/// [`RecordingTransport`](crate::transport::RecordingTransport) and served back by
+/// [`ReplayTransport`](crate::transport::ReplayTransport).
+///
+/// Cassettes are stored as pretty-printed JSON so that they can be reviewed and
+/// committed alongside the tests that use them.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Cassette {
+    /// The recorded interactions, in the order they happened.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the file cannot be read, or an
+    /// [`Error::JsonParse`] if it is not a valid cassette.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path).map_err(|e| {
+            Error::Client(format!(
+                "Failed to read cassette '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    /// Writes the cassette to a JSON file, replacing any existing content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, contents).map_err(|e| {
+            Error::Client(format!(
+                "Failed to write cassette '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// A single request/response pair in a [`Cassette`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    /// The request that was sent.
+    pub request: RecordedRequest,
+    /// The response that was received.
+    pub response: RecordedResponse,
+}
+
+/// The parts of an [`HttpRequest`] that are used to match replayed requests.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// The HTTP method (e.g. `"POST"`).
+    pub method: String,
+    /// The URL path of the API endpoint (e.g. `"/api/chat"`).
+    pub path: String,
+    /// The JSON request body, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+impl RecordedRequest {
+    /// Returns `true` if `request` has the same method, path and JSON body.
+    ///
+    /// Bodies are compared as JSON values, so key order and whitespace do not matter.
+    pub fn matches(&self, request: &HttpRequest) -> bool {
+        self.method == request.verb.as_str()
+            && self.path == request.url
+            && self.body == request.json_body()
+    }
+}
+
+impl From<&HttpRequest> for RecordedRequest {
+    fn from(request: &HttpRequest) -> Self {
+        Self {
+            method: request.verb.as_str().to_string(),
+            path: request.url.clone(),
+            body: request.json_body(),
+        }
+    }
+}
+
+/// A recorded response, either with a complete body or as a sequence of stream chunks.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The response headers. Repeated headers are joined with `", "`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The complete response body of a non-streaming request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<RecordedBytes>,
+    /// The body chunks of a streaming request, in the order they were received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunks: Option<Vec<RecordedChunk>>,
+    /// The error that ended the stream early, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whether the client dropped the stream before it ended, so that the recorded chunks
+    /// are only the beginning of the response.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl RecordedResponse {
+    /// Returns the whole body, concatenating stream chunks if the response was streamed.
+    pub fn body_bytes(&self) -> Bytes {
+        match (&self.body, &self.chunks) {
+            (Some(body), _) => body.to_bytes(),
+            (None, Some(chunks)) => chunks
+                .iter()
+                .flat_map(|chunk| chunk.data.to_bytes())
+                .collect::<Vec<u8>>()
+                .into(),
+            (None, None) => Bytes::new(),
+        }
+    }
+}
+
+/// A single body chunk of a streamed response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedChunk {
+    /// Milliseconds elapsed since the previous chunk (or since the response headers for the first one).
+    pub delay_ms: u64,
+    /// The chunk payload.
+    pub data: RecordedBytes,
+}
+
+/// Raw bytes in a cassette, kept as text when they are valid UTF-8 to keep cassettes readable.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RecordedBytes {
+    /// UTF-8 text.
+    Text(String),
+    /// Arbitrary bytes, e.g. a chunk that was split in the middle of a UTF-8 sequence.
+    Binary(Vec<u8>),
+}
+
+impl RecordedBytes {
+    /// Returns the recorded bytes.
+    pub fn to_bytes(&self) -> Bytes {
+        match self {
+            RecordedBytes::Text(text) => Bytes::copy_from_slice(text.as_bytes()),
+            RecordedBytes::Binary(bytes) => Bytes::copy_from_slice(bytes),
+        }
+    }
+}
+
+impl From<&[u8]> for RecordedBytes {
+    fn from(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBytes::Text(text.to_string()),
+            Err(_) => RecordedBytes::Binary(bytes.to_vec()),
+        }
+    }
+}
+
+/// Flattens a [`HeaderMap`] into the cassette representation.
+pub(crate) fn record_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut recorded = BTreeMap::<String, String>::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        recorded
+            .entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+    recorded
+}
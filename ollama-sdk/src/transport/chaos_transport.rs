@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures::stream::{self, StreamExt};
+
+use crate::transport::Transport;
+use crate::types::{ByteStream, HttpRequest, HttpResponse, HttpStreamResponse};
+use crate::{Error, Result};
+
+/// A fault that a [`ChaosTransport`] can inject into a request or a stream chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChaosFault {
+    /// Fails the request (or the stream, when scheduled on a chunk) with an [`Error::Transport`].
+    Fail,
+    /// Waits before sending the request or delivering the chunk.
+    Delay(Duration),
+    /// Splits the chunk in two at the given byte offset, which may fall inside a UTF-8 sequence.
+    SplitAt(usize),
+    /// Keeps only the first bytes of the chunk, up to the given offset, and ends the stream.
+    TruncateAt(usize),
+    /// Overwrites the byte at the given offset with `#`, turning the JSON line into garbage.
+    CorruptAt(usize),
+}
+
+/// Probabilities of the randomly injected faults.
+#[derive(Debug, Clone, Default)]
+struct ChaosRates {
+    request_failure: f64,
+    request_delay: Option<(f64, Duration)>,
+    chunk_delay: Option<(f64, Duration)>,
+    split: f64,
+    truncate: f64,
+    stream_error: f64,
+    corrupt: f64,
+}
+
+/// A [`Transport`] wrapper that injects faults into requests and streamed responses.
+///
+/// Faults are injected either randomly, with a configured probability per request or
+/// per chunk, or deterministically through a schedule keyed by request (and chunk) index.
+/// Random faults are driven by a seeded generator, so a failing run can be reproduced
+/// with the same [`seed`](ChaosTransport::seed).
+///
+/// This is meant to exercise [`GenericStreamParser`](crate::parser::GenericStreamParser)
+/// and application retry logic against dropped streams, slow tokens and malformed lines.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use ollama_sdk::transport::{ChaosFault, ChaosTransport, MockTransport};
+///
+/// let chaos = ChaosTransport::new(Arc::new(MockTransport::new()))
+///     .seed(7)
+///     .split_rate(0.5)
+///     .chunk_delay(0.1, Duration::from_millis(200))
+///     .on_request(0, ChaosFault::Fail)
+///     .on_chunk(1, 3, ChaosFault::TruncateAt(10));
+/// ```
+pub struct ChaosTransport {
+    inner: Arc<dyn Transport + Send + Sync>,
+    rng: Mutex<fastrand::Rng>,
+    request_count: AtomicUsize,
+    rates: ChaosRates,
+    request_schedule: HashMap<usize, Vec<ChaosFault>>,
+    chunk_schedule: Arc<HashMap<(usize, usize), Vec<ChaosFault>>>,
+}
+
+impl ChaosTransport {
+    /// Creates a new [`ChaosTransport`] wrapping `inner`, with no faults configured.
+    pub fn new(inner: Arc<dyn Transport + Send + Sync>) -> Self {
+        Self {
+            inner,
+            rng: Mutex::new(fastrand::Rng::with_seed(0)),
+            request_count: AtomicUsize::new(0),
+            rates: ChaosRates::default(),
+            request_schedule: HashMap::new(),
+            chunk_schedule: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Seeds the generator used for random faults. The default seed is `0`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(fastrand::Rng::with_seed(seed));
+        self
+    }
+
+    /// Fails requests with the given probability before they reach the wrapped transport.
+    pub fn request_failure_rate(mut self, probability: f64) -> Self {
+        self.rates.request_failure = probability;
+        self
+    }
+
+    /// Delays requests by `delay` with the given probability.
+    pub fn request_delay(mut self, probability: f64, delay: Duration) -> Self {
+        self.rates.request_delay = Some((probability, delay));
+        self
+    }
+
+    /// Delays stream chunks by `delay` with the given probability, simulating slow tokens.
+    pub fn chunk_delay(mut self, probability: f64, delay: Duration) -> Self {
+        self.rates.chunk_delay = Some((probability, delay));
+        self
+    }
+
+    /// Splits stream chunks at a random byte offset with the given probability.
+    pub fn split_rate(mut self, probability: f64) -> Self {
+        self.rates.split = probability;
+        self
+    }
+
+    /// Truncates the stream at a random offset of a chunk with the given probability.
+    ///
+    /// Non-streaming bodies are truncated with the same probability.
+    pub fn truncate_rate(mut self, probability: f64) -> Self {
+        self.rates.truncate = probability;
+        self
+    }
+
+    /// Ends the stream with an [`Error::Transport`] instead of a chunk with the given probability.
+    pub fn stream_error_rate(mut self, probability: f64) -> Self {
+        self.rates.stream_error = probability;
+        self
+    }
+
+    /// Corrupts a random byte of a chunk with the given probability.
+    ///
+    /// Non-streaming bodies are corrupted with the same probability.
+    pub fn corrupt_rate(mut self, probability: f64) -> Self {
+        self.rates.corrupt = probability;
+        self
+    }
+
+    /// Injects `fault` into the request with the given index (counting from `0`).
+    ///
+    /// Only [`ChaosFault::Fail`] and [`ChaosFault::Delay`] apply to requests; for
+    /// non-streaming requests, [`ChaosFault::TruncateAt`] and [`ChaosFault::CorruptAt`]
+    /// are applied to the response body.
+    pub fn on_request(mut self, request_index: usize, fault: ChaosFault) -> Self {
+        self.request_schedule
+            .entry(request_index)
+            .or_default()
+            .push(fault);
+        self
+    }
+
+    /// Injects `fault` into the chunk with the given index of the given request's stream.
+    pub fn on_chunk(mut self, request_index: usize, chunk_index: usize, fault: ChaosFault) -> Self {
+        Arc::make_mut(&mut self.chunk_schedule)
+            .entry((request_index, chunk_index))
+            .or_default()
+            .push(fault);
+        self
+    }
+
+    /// Returns the number of requests that went through this transport.
+    pub fn request_count(&self) -> usize {
+        self.request_count.load(Ordering::SeqCst)
+    }
+
+    /// Assigns the next request index and applies the request-level faults.
+    ///
+    /// Returns the request index, a generator for the rest of the request and the
+    /// scheduled faults that apply to a non-streaming body.
+    async fn before_request(&self) -> Result<(usize, fastrand::Rng, Vec<ChaosFault>)> {
+        let request_index = self.request_count.fetch_add(1, Ordering::SeqCst);
+        let mut rng = self.rng.lock().unwrap().fork();
+
+        let mut faults = self
+            .request_schedule
+            .get(&request_index)
+            .cloned()
+            .unwrap_or_default();
+        if rng.f64() < self.rates.request_failure {
+            faults.push(ChaosFault::Fail);
+        }
+        if let Some((probability, delay)) = self.rates.request_delay {
+            if rng.f64() < probability {
+                faults.push(ChaosFault::Delay(delay));
+            }
+        }
+
+        let mut body_faults = Vec::new();
+        for fault in faults {
+            match fault {
+                ChaosFault::Delay(delay) => tokio::time::sleep(delay).await,
+                ChaosFault::Fail => return Err(injected_error("request failure")),
+                fault => body_faults.push(fault),
+            }
+        }
+
+        Ok((request_index, rng, body_faults))
+    }
+}
+
+#[async_trait]
+impl Transport for ChaosTransport {
+    /// Forwards the request to the wrapped transport, injecting request and body faults.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let (_, mut rng, mut body_faults) = self.before_request().await?;
+        let mut response = self.inner.send_http_request(request).await?;
+
+        if let Some(body) = response.body.take() {
+            if !body.is_empty() {
+                if rng.f64() < self.rates.corrupt {
+                    body_faults.push(ChaosFault::CorruptAt(rng.usize(..body.len())));
+                }
+                if rng.f64() < self.rates.truncate {
+                    body_faults.push(ChaosFault::TruncateAt(rng.usize(..body.len())));
+                }
+            }
+            let mut body = body;
+            for fault in body_faults {
+                match fault {
+                    ChaosFault::CorruptAt(offset) => body = corrupt(body, offset),
+                    ChaosFault::TruncateAt(offset) => body = body.slice(..offset.min(body.len())),
+                    _ => {}
+                }
+            }
+            response.body = Some(body);
+        }
+
+        Ok(response)
+    }
+
+    /// Forwards the request to the wrapped transport and injects faults into the returned stream.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let (request_index, rng, _) = self.before_request().await?;
+        let mut response = self.inner.send_http_stream_request(request).await?;
+
+        let state = ChaosStream {
+            inner: response.body,
+            rng,
+            rates: self.rates.clone(),
+            schedule: self.chunk_schedule.clone(),
+            request_index,
+            chunk_index: 0,
+            pending: None,
+            done: false,
+        };
+        response.body = stream::unfold(state, ChaosStream::next_chunk)
+            .fuse()
+            .boxed();
+
+        Ok(response)
+    }
+}
+
+/// The state of a stream with injected faults.
+struct ChaosStream {
+    inner: ByteStream,
+    rng: fastrand::Rng,
+    rates: ChaosRates,
+    schedule: Arc<HashMap<(usize, usize), Vec<ChaosFault>>>,
+    request_index: usize,
+    chunk_index: usize,
+    /// The second half of a split chunk, delivered before polling the inner stream again.
+    pending: Option<Bytes>,
+    done: bool,
+}
+
+impl ChaosStream {
+    async fn next_chunk(mut self) -> Option<(Result<Bytes>, Self)> {
+        if self.done {
+            return None;
+        }
+        if let Some(rest) = self.pending.take() {
+            return Some((Ok(rest), self));
+        }
+
+        let mut chunk = match self.inner.next().await? {
+            Ok(chunk) => chunk,
+            Err(e) => return Some((Err(e), self)),
+        };
+
+        for fault in self.faults_for(&chunk) {
+            match fault {
+                ChaosFault::Delay(delay) => tokio::time::sleep(delay).await,
+                ChaosFault::Fail => {
+                    self.done = true;
+                    return Some((Err(injected_error("stream error")), self));
+                }
+                ChaosFault::CorruptAt(offset) => chunk = corrupt(chunk, offset),
+                ChaosFault::TruncateAt(offset) => {
+                    chunk = chunk.slice(..offset.min(chunk.len()));
+                    self.done = true;
+                }
+                ChaosFault::SplitAt(offset) => {
+                    if offset > 0 && offset < chunk.len() {
+                        self.pending = Some(chunk.slice(offset..));
+                        chunk = chunk.slice(..offset);
+                    }
+                }
+            }
+        }
+
+        Some((Ok(chunk), self))
+    }
+
+    /// Collects the scheduled and randomly drawn faults for the next chunk.
+    fn faults_for(&mut self, chunk: &Bytes) -> Vec<ChaosFault> {
+        let mut faults = self
+            .schedule
+            .get(&(self.request_index, self.chunk_index))
+            .cloned()
+            .unwrap_or_default();
+        self.chunk_index += 1;
+
+        let rng = &mut self.rng;
+        if let Some((probability, delay)) = self.rates.chunk_delay {
+            if rng.f64() < probability {
+                faults.push(ChaosFault::Delay(delay));
+            }
+        }
+        if rng.f64() < self.rates.stream_error {
+            faults.push(ChaosFault::Fail);
+        }
+        if !chunk.is_empty() {
+            if rng.f64() < self.rates.corrupt {
+                faults.push(ChaosFault::CorruptAt(rng.usize(..chunk.len())));
+            }
+            if rng.f64() < self.rates.truncate {
+                faults.push(ChaosFault::TruncateAt(rng.usize(..chunk.len())));
+            }
+        }
+        if chunk.len() > 1 && rng.f64() < self.rates.split {
+            faults.push(ChaosFault::SplitAt(rng.usize(1..chunk.len())));
+        }
+
+        faults
+    }
+}
+
+fn injected_error(what: &str) -> Error {
+    Error::Transport(format!("chaos: injected {}", what).into())
+}
+
+/// Overwrites the byte at `offset` with `#`, leaving line breaks intact so that
+/// only the line containing the byte is affected.
+fn corrupt(chunk: Bytes, offset: usize) -> Bytes {
+    let Some(offset) = (offset..chunk.len())
+        .chain(0..offset.min(chunk.len()))
+        .find(|&i| chunk[i] != b'\n')
+    else {
+        return chunk;
+    };
+    let mut corrupted = BytesMut::from(chunk.as_ref());
+    corrupted[offset] = b'#';
+    corrupted.freeze()
+}
@@ -0,0 +1,350 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "metrics")]
+use metrics::counter;
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::transport::{is_server_failure, Transport};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse};
+use crate::{Error, Result};
+
+/// The state of a [`CircuitBreakerTransport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow through and their outcomes are tracked.
+    Closed,
+    /// Requests fail fast with [`Error::CircuitOpen`] without reaching the server.
+    Open,
+    /// A limited number of probe requests are let through to test whether the server recovered.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Returns the lowercase name of the state, as used in logs and metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// A [`Transport`] wrapper that stops sending requests to a server that keeps failing.
+///
+/// While **closed**, the outcome of every request within a sliding
+/// [`window`](CircuitBreakerTransport::window) is tracked. Once at least
+/// [`minimum_requests`](CircuitBreakerTransport::minimum_requests) requests were seen and
+/// the share of failures reaches the
+/// [`failure_rate_threshold`](CircuitBreakerTransport::failure_rate_threshold), the circuit
+/// **opens**: requests fail immediately with [`Error::CircuitOpen`] for the
+/// [`open_duration`](CircuitBreakerTransport::open_duration). The circuit then becomes
+/// **half-open** and lets up to [`probes`](CircuitBreakerTransport::probes) requests through;
+/// if they all succeed the circuit closes, and any failure opens it again.
+///
+/// Only connection errors, timeouts and responses with a 5xx status count as failures;
+/// responses with a 4xx status are the caller's fault and leave the circuit alone. For
+/// streaming requests the outcome is taken when the response head arrives, and an error
+/// later in the stream is counted as an additional failure.
+///
+/// State changes are logged with the `tracing` feature and counted in the
+/// `ollama_client.circuit_breaker_transitions_total` metric with the `metrics` feature.
+pub struct CircuitBreakerTransport {
+    inner: Arc<dyn Transport + Send + Sync>,
+    config: BreakerConfig,
+    breaker: Arc<Mutex<Breaker>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BreakerConfig {
+    failure_rate_threshold: f64,
+    minimum_requests: usize,
+    window: Duration,
+    open_duration: Duration,
+    probes: u32,
+}
+
+/// The mutable state shared by all requests going through the breaker.
+struct Breaker {
+    state: CircuitState,
+    /// Outcomes (`true` for failures) of the requests in the current window.
+    outcomes: VecDeque<(Instant, bool)>,
+    opened_at: Instant,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+/// How a request was admitted by the breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    Normal,
+    Probe,
+}
+
+impl Breaker {
+    fn transition(&mut self, to: CircuitState) {
+        if self.state == to {
+            return;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            from = self.state.as_str(),
+            to = to.as_str(),
+            "circuit breaker state changed"
+        );
+        #[cfg(feature = "metrics")]
+        counter!("ollama_client.circuit_breaker_transitions_total", "state" => to.as_str())
+            .increment(1);
+
+        self.state = to;
+        self.probes_in_flight = 0;
+        self.probe_successes = 0;
+        match to {
+            CircuitState::Open => self.opened_at = Instant::now(),
+            CircuitState::Closed => self.outcomes.clear(),
+            CircuitState::HalfOpen => {}
+        }
+    }
+
+    fn admit(&mut self, config: &BreakerConfig) -> Result<Admission> {
+        if self.state == CircuitState::Open {
+            let elapsed = self.opened_at.elapsed();
+            if elapsed < config.open_duration {
+                return Err(rejected(config.open_duration - elapsed));
+            }
+            self.transition(CircuitState::HalfOpen);
+        }
+
+        match self.state {
+            CircuitState::HalfOpen
+                if self.probes_in_flight + self.probe_successes < config.probes =>
+            {
+                self.probes_in_flight += 1;
+                Ok(Admission::Probe)
+            }
+            CircuitState::HalfOpen => Err(rejected(Duration::ZERO)),
+            _ => Ok(Admission::Normal),
+        }
+    }
+
+    fn record(&mut self, config: &BreakerConfig, admission: Admission, failed: bool) {
+        if admission == Admission::Probe {
+            if self.state != CircuitState::HalfOpen {
+                return;
+            }
+            self.probes_in_flight = self.probes_in_flight.saturating_sub(1);
+            if failed {
+                self.transition(CircuitState::Open);
+            } else {
+                self.probe_successes += 1;
+                if self.probe_successes >= config.probes {
+                    self.transition(CircuitState::Closed);
+                }
+            }
+            return;
+        }
+
+        match self.state {
+            CircuitState::Closed => {
+                let now = Instant::now();
+                self.outcomes.push_back((now, failed));
+                while self
+                    .outcomes
+                    .front()
+                    .is_some_and(|(at, _)| now.duration_since(*at) > config.window)
+                {
+                    self.outcomes.pop_front();
+                }
+
+                let failures = self.outcomes.iter().filter(|(_, failed)| *failed).count();
+                let total = self.outcomes.len();
+                if total >= config.minimum_requests
+                    && failures as f64 / total as f64 >= config.failure_rate_threshold
+                {
+                    self.transition(CircuitState::Open);
+                }
+            }
+            // A request admitted before the circuit opened failed while testing recovery.
+            CircuitState::HalfOpen if failed => self.transition(CircuitState::Open),
+            _ => {}
+        }
+    }
+
+    /// Gives back the probe slot of a request that was dropped before completing.
+    fn release(&mut self, admission: Admission) {
+        if admission == Admission::Probe && self.state == CircuitState::HalfOpen {
+            self.probes_in_flight = self.probes_in_flight.saturating_sub(1);
+        }
+    }
+}
+
+fn rejected(retry_after: Duration) -> Error {
+    #[cfg(feature = "metrics")]
+    counter!("ollama_client.circuit_breaker_rejections_total").increment(1);
+
+    Error::CircuitOpen { retry_after }
+}
+
+/// Records the outcome of an admitted request, releasing its probe slot if dropped early.
+struct Permit<'a> {
+    transport: &'a CircuitBreakerTransport,
+    admission: Option<Admission>,
+}
+
+impl Permit<'_> {
+    fn finish<T>(mut self, result: &Result<T>) {
+        if let Some(admission) = self.admission.take() {
+            let failed = result.as_ref().err().is_some_and(is_server_failure);
+            self.transport.breaker.lock().unwrap().record(
+                &self.transport.config,
+                admission,
+                failed,
+            );
+        }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if let Some(admission) = self.admission.take() {
+            self.transport.breaker.lock().unwrap().release(admission);
+        }
+    }
+}
+
+impl CircuitBreakerTransport {
+    /// Creates a new [`CircuitBreakerTransport`] wrapping `inner`.
+    ///
+    /// By default the circuit opens when at least half of at least 10 requests in the last
+    /// 30 seconds failed, stays open for 30 seconds, and closes after 1 successful probe.
+    pub fn new(inner: Arc<dyn Transport + Send + Sync>) -> Self {
+        Self {
+            inner,
+            config: BreakerConfig {
+                failure_rate_threshold: 0.5,
+                minimum_requests: 10,
+                window: Duration::from_secs(30),
+                open_duration: Duration::from_secs(30),
+                probes: 1,
+            },
+            breaker: Arc::new(Mutex::new(Breaker {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: Instant::now(),
+                probes_in_flight: 0,
+                probe_successes: 0,
+            })),
+        }
+    }
+
+    /// Sets the share of failed requests that opens the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure_rate_threshold` is not greater than `0.0` and at most `1.0`.
+    pub fn failure_rate_threshold(mut self, failure_rate_threshold: f64) -> Self {
+        assert!(
+            failure_rate_threshold > 0.0 && failure_rate_threshold <= 1.0,
+            "failure rate threshold must be in (0, 1], got {}",
+            failure_rate_threshold
+        );
+        self.config.failure_rate_threshold = failure_rate_threshold;
+        self
+    }
+
+    /// Sets the number of requests that must be seen in the window before the circuit can open.
+    pub fn minimum_requests(mut self, minimum_requests: usize) -> Self {
+        self.config.minimum_requests = minimum_requests.max(1);
+        self
+    }
+
+    /// Sets the length of the sliding window in which request outcomes are tracked.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.config.window = window;
+        self
+    }
+
+    /// Sets how long the circuit stays open before probe requests are let through.
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.config.open_duration = open_duration;
+        self
+    }
+
+    /// Sets the number of successful probe requests needed to close a half-open circuit.
+    pub fn probes(mut self, probes: u32) -> Self {
+        self.config.probes = probes.max(1);
+        self
+    }
+
+    /// Returns the current state of the circuit.
+    ///
+    /// An open circuit whose open duration has elapsed is reported as open until the next
+    /// request turns it half-open.
+    pub fn state(&self) -> CircuitState {
+        self.breaker.lock().unwrap().state
+    }
+
+    fn admit(&self) -> Result<Permit<'_>> {
+        let admission = self.breaker.lock().unwrap().admit(&self.config)?;
+        Ok(Permit {
+            transport: self,
+            admission: Some(admission),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for CircuitBreakerTransport {
+    /// Forwards the request to the wrapped transport unless the circuit is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::CircuitOpen`] if the circuit is open, or the error of the
+    /// wrapped transport.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let permit = self.admit()?;
+        let result = self.inner.send_http_request(request).await;
+        permit.finish(&result);
+        result
+    }
+
+    /// Forwards the streaming request to the wrapped transport unless the circuit is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::CircuitOpen`] if the circuit is open, or the error of the
+    /// wrapped transport.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let permit = self.admit()?;
+        let result = self.inner.send_http_stream_request(request).await;
+        permit.finish(&result);
+        let mut response = result?;
+
+        let breaker = self.breaker.clone();
+        let config = self.config;
+        response.body = response
+            .body
+            .inspect(move |item| {
+                if let Err(e) = item {
+                    if is_server_failure(e) {
+                        breaker
+                            .lock()
+                            .unwrap()
+                            .record(&config, Admission::Normal, true);
+                    }
+                }
+            })
+            .boxed();
+
+        Ok(response)
+    }
+}
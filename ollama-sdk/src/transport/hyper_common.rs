@@ -0,0 +1,107 @@
+//! Request and response conversions shared by the transports built directly on `hyper`.
+
+use std::time::Instant;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{AUTHORIZATION, HOST};
+use hyper::{Method, Request, Response};
+
+use crate::transport::status_error;
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse, HttpVerb, ResponseTiming};
+use crate::{Error, Result};
+
+/// Builds a `hyper` request for `request`, sent to `uri` with the given `Host` header.
+pub(crate) fn build_request(
+    request: HttpRequest,
+    uri: &str,
+    host: &str,
+    api_key: Option<&str>,
+) -> Result<Request<Full<Bytes>>> {
+    let method = match request.verb {
+        HttpVerb::GET => Method::GET,
+        HttpVerb::POST => Method::POST,
+        HttpVerb::PUT => Method::PUT,
+        HttpVerb::DELETE => Method::DELETE,
+    };
+
+    let mut request_builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(HOST, host);
+
+    if let Some(api_key) = api_key {
+        request_builder = request_builder.header(AUTHORIZATION, format!("Bearer {}", api_key));
+    }
+
+    if let Some(headers) = request_builder.headers_mut() {
+        headers.extend(request.headers);
+    }
+
+    request_builder
+        .body(Full::new(request.body.unwrap_or_default()))
+        .map_err(|e| Error::Client(e.to_string()))
+}
+
+/// Turns a non-success response into an [`Error::Http`] carrying the Ollama error message.
+pub(crate) async fn check_status(response: Response<Incoming>) -> Result<Response<Incoming>> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| Error::Transport(Box::new(e)))?
+        .to_bytes();
+    Err(status_error(status, &body))
+}
+
+/// Reads the whole body of `response` into an [`HttpResponse`].
+pub(crate) async fn into_http_response(
+    response: Response<Incoming>,
+    started_at: Instant,
+) -> Result<HttpResponse> {
+    let time_to_headers = started_at.elapsed();
+    let (parts, body) = response.into_parts();
+    let response_bytes = body
+        .collect()
+        .await
+        .map_err(|e| Error::Transport(Box::new(e)))?
+        .to_bytes();
+    Ok(HttpResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body: Some(response_bytes),
+        timing: ResponseTiming {
+            time_to_headers,
+            total: Some(started_at.elapsed()),
+        },
+    })
+}
+
+/// Wraps the body of `response` into an [`HttpStreamResponse`].
+pub(crate) fn into_stream_response(
+    response: Response<Incoming>,
+    started_at: Instant,
+) -> HttpStreamResponse {
+    let time_to_headers = started_at.elapsed();
+    let (parts, body) = response.into_parts();
+    let body = body
+        .into_data_stream()
+        .map(|item| item.map_err(|e| Error::Transport(Box::new(e))))
+        .boxed();
+    HttpStreamResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body,
+        timing: ResponseTiming {
+            time_to_headers,
+            total: None,
+        },
+    }
+}
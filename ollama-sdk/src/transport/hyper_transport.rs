@@ -0,0 +1,134 @@
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::Uri;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::Response;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+
+use crate::transport::hyper_common::{
+    build_request, check_status, into_http_response, into_stream_response,
+};
+use crate::transport::{with_timeout, Transport};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse};
+use crate::{Error, Result};
+
+/// A lean [`Transport`] implementation built directly on `hyper`, enabled with the `hyper` feature.
+///
+/// Connections are kept alive and reused across requests through `hyper-util`'s pooled
+/// client. Only plain `http://` hosts are supported, which covers the usual case of an
+/// Ollama server on the local machine or a private network; use
+/// [`ReqwestTransport`](crate::transport::ReqwestTransport) for `https://` hosts.
+pub struct HyperTransport {
+    client: Client<HttpConnector, Full<Bytes>>,
+    /// The base URL without a trailing slash, which request paths are appended to.
+    base: String,
+    authority: String,
+    api_key: Option<String>,
+}
+
+impl HyperTransport {
+    /// Creates a new `HyperTransport`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the Ollama server, e.g. `http://127.0.0.1:11434`. A
+    ///   path, e.g. `http://gateway/ollama`, is kept in front of the API paths.
+    /// * `api_key` - An optional API key for authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the URL is invalid or does not use the `http` scheme.
+    pub fn new(base_url: &str, api_key: Option<String>) -> Result<Self> {
+        let uri: Uri = base_url
+            .parse()
+            .map_err(|e| Error::Client(format!("Invalid base URL: {}", e)))?;
+        if uri.scheme_str() != Some("http") {
+            return Err(Error::Client(format!(
+                "HyperTransport only supports http:// hosts, got '{}'",
+                base_url
+            )));
+        }
+        let authority = uri
+            .authority()
+            .ok_or_else(|| {
+                Error::Client(format!("Invalid base URL: missing host in '{}'", base_url))
+            })?
+            .to_string();
+
+        let path = uri.path().trim_end_matches('/');
+
+        let client = Client::builder(TokioExecutor::new()).build_http();
+        Ok(Self {
+            client,
+            base: format!("http://{}{}", authority, path),
+            authority,
+            api_key,
+        })
+    }
+
+    /// Helper to build and send a request, checking the response status.
+    async fn build_and_send_request(&self, request: HttpRequest) -> Result<Response<Incoming>> {
+        let uri = format!("{}{}", self.base, request.url);
+        let hyper_request = build_request(request, &uri, &self.authority, self.api_key.as_deref())?;
+
+        let response = self
+            .client
+            .request(hyper_request)
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?;
+
+        check_status(response).await
+    }
+}
+
+#[async_trait]
+impl Transport for HyperTransport {
+    /// Sends a non-streaming HTTP request using `hyper`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`HttpRequest`] to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Transport`] if the request fails, times out or the response cannot
+    /// be read, and an [`Error::Http`] if the server answers with a non-success status.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        with_timeout(timeout, async {
+            let response = self.build_and_send_request(request).await?;
+            into_http_response(response, started_at).await
+        })
+        .await
+    }
+
+    /// Sends a streaming HTTP request using `hyper` and returns a stream of response bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`HttpRequest`] to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Transport`] if the request fails, times out or the stream cannot be
+    /// established, and an [`Error::Http`] if the server answers with a non-success status.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        let response = with_timeout(timeout, self.build_and_send_request(request)).await?;
+        Ok(into_stream_response(response, started_at))
+    }
+}
@@ -1,5 +1,7 @@
-use std::pin::Pin;
+use std::collections::VecDeque;
+use std::fmt;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[cfg(feature = "tracing")]
 use tracing::instrument;
@@ -7,30 +9,258 @@ use tracing::instrument;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::stream::{self};
-use futures::Stream;
 use futures::StreamExt;
+use http::header::{HeaderName, HeaderValue};
+use http::{HeaderMap, StatusCode};
+use serde_json::Value;
 
-use crate::transport::Transport;
+use crate::transport::{status_error, Transport};
 use crate::types::chat::ChatStreamEvent;
-use crate::types::{HttpRequest, HttpResponse};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse, HttpVerb, OllamaError};
 use crate::{Error, Result};
 
+type BodyPredicate = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+type ErrorFactory = Arc<dyn Fn() -> Error + Send + Sync>;
+
 /// A mock implementation of the [`Transport`] trait for testing purposes.
 ///
-/// This transport allows you to pre-configure responses for both streaming
-/// and non-streaming HTTP requests, enabling isolated testing of client logic
-/// without making actual network calls.
+/// The mock is scripted with [`MockRoute`]s. Each route matches requests by verb, path,
+/// and optionally a predicate on the JSON body, and serves its queued [`MockResponse`]s
+/// one per matching request. Routes are tried in the order they were added, and routes
+/// whose queue is exhausted are skipped. Requests that match no route fail with an
+/// [`Error::Client`] naming the request, so a test cannot pass on a response it never
+/// scripted. Before 0.5.0 such requests got an empty response instead; queue an explicit
+/// response, e.g. `MockResponse::body(Vec::new())`, where a test relied on that.
+///
+/// Every request is captured, so tests can inspect what the client sent with
+/// [`MockTransport::requests`] or the `assert_*` helpers.
+///
+/// ```
+/// use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+///
+/// let mock = MockTransport::new().with_route(
+///     MockRoute::post("/api/chat")
+///         .when_body(|body| body["model"] == "llama3.2")
+///         .respond(MockResponse::ndjson([
+///             r#"{"model":"llama3.2","message":{"role":"assistant","content":"Hi"},"done":true}"#,
+///         ])),
+/// );
+/// ```
 #[derive(Clone, Default)]
 pub struct MockTransport {
-    /// Stores a sequence of [`ChatStreamEvent`]s to be returned for streaming chat requests.
-    chat_stream_events: Arc<Mutex<Vec<ChatStreamEvent>>>,
-    /// Stores a sequence of raw `Bytes` to be returned for streaming generate requests.
-    generate_stream_bytes: Arc<Mutex<Vec<Bytes>>>,
-    /// Stores a sequence of raw JSON strings to be returned for streaming chat requests.
-    raw_chat_stream_strings: Arc<Mutex<Vec<String>>>,
-
-    /// Stores an optional [`HttpResponse`] to be returned for non-streaming HTTP requests.
-    non_streaming_http_response: Arc<Mutex<Option<HttpResponse>>>,
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    routes: Vec<MockRoute>,
+    requests: Vec<CapturedRequest>,
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    /// The HTTP verb of the request.
+    pub verb: HttpVerb,
+    /// The URL path of the request (e.g. `"/api/chat"`).
+    pub path: String,
+    /// The JSON body of the request, or [`Value::Null`] if there was none.
+    pub body: Value,
+    /// The additional headers of the request.
+    pub headers: HeaderMap,
+    /// Whether the request was sent through [`Transport::send_http_stream_request`].
+    pub streaming: bool,
+}
+
+/// A route of a [`MockTransport`], matching requests and serving queued responses.
+#[derive(Clone)]
+pub struct MockRoute {
+    verb: Option<HttpVerb>,
+    path: Option<String>,
+    streaming: Option<bool>,
+    predicate: Option<BodyPredicate>,
+    responses: VecDeque<MockResponse>,
+}
+
+impl MockRoute {
+    /// Creates a route matching requests with the given verb and path.
+    pub fn new(verb: HttpVerb, path: impl Into<String>) -> Self {
+        Self {
+            verb: Some(verb),
+            path: Some(path.into()),
+            ..Self::any()
+        }
+    }
+
+    /// Creates a route matching `GET` requests to `path`.
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(HttpVerb::GET, path)
+    }
+
+    /// Creates a route matching `POST` requests to `path`.
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(HttpVerb::POST, path)
+    }
+
+    /// Creates a route matching every request.
+    pub fn any() -> Self {
+        Self {
+            verb: None,
+            path: None,
+            streaming: None,
+            predicate: None,
+            responses: VecDeque::new(),
+        }
+    }
+
+    /// Only matches requests whose JSON body satisfies `predicate`.
+    ///
+    /// Requests without a body are checked against [`Value::Null`].
+    pub fn when_body<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Only matches streaming (`true`) or non-streaming (`false`) requests.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = Some(streaming);
+        self
+    }
+
+    /// Queues a response. Responses are served in the order they were queued.
+    pub fn respond(mut self, response: MockResponse) -> Self {
+        self.responses.push_back(response);
+        self
+    }
+
+    fn matches(&self, request: &CapturedRequest) -> bool {
+        !self.responses.is_empty()
+            && self.verb.map_or(true, |verb| verb == request.verb)
+            && self
+                .path
+                .as_ref()
+                .map_or(true, |path| *path == request.path)
+            && self.streaming.map_or(true, |s| s == request.streaming)
+            && self
+                .predicate
+                .as_ref()
+                .map_or(true, |predicate| predicate(&request.body))
+    }
+}
+
+/// A scripted response of a [`MockRoute`].
+///
+/// Full-body responses are served as a single chunk to streaming requests, and chunked
+/// responses are concatenated when served to non-streaming requests.
+#[derive(Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: MockBody,
+    stream_error: Option<ErrorFactory>,
+    delay: Option<Duration>,
+    chunk_delay: Option<Duration>,
+}
+
+#[derive(Clone)]
+enum MockBody {
+    Full(Bytes),
+    Chunks(Vec<Bytes>),
+    Error(ErrorFactory),
+}
+
+impl MockResponse {
+    fn with_body(body: MockBody) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body,
+            stream_error: None,
+            delay: None,
+            chunk_delay: None,
+        }
+    }
+
+    /// A response with the given raw body.
+    pub fn body(body: impl Into<Bytes>) -> Self {
+        Self::with_body(MockBody::Full(body.into()))
+    }
+
+    /// A response with the given JSON body.
+    pub fn json(body: Value) -> Self {
+        Self::body(body.to_string()).header("content-type", "application/json")
+    }
+
+    /// A streamed response made of the given raw chunks.
+    pub fn chunks(chunks: impl IntoIterator<Item = impl Into<Bytes>>) -> Self {
+        Self::with_body(MockBody::Chunks(
+            chunks.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    /// A streamed response sending each line as its own newline-terminated chunk.
+    pub fn ndjson(lines: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::chunks(lines.into_iter().map(|line| {
+            let mut line = line.into();
+            line.push('\n');
+            Bytes::from(line)
+        }))
+        .header("content-type", "application/x-ndjson")
+    }
+
+    /// A response that fails the request with the error built by `error`.
+    pub fn error<F>(error: F) -> Self
+    where
+        F: Fn() -> Error + Send + Sync + 'static,
+    {
+        Self::with_body(MockBody::Error(Arc::new(error)))
+    }
+
+    /// Sets the status code of the response.
+    ///
+    /// Like the HTTP transports, the mock fails requests answered with a non-success status
+    /// with an [`Error::Http`] carrying the Ollama error message of the body.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Adds a header to the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header name or value is invalid.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).expect("invalid mock header name"),
+            HeaderValue::from_str(value).expect("invalid mock header value"),
+        );
+        self
+    }
+
+    /// Ends the stream with the error built by `error` after all chunks were sent.
+    pub fn then_error<F>(mut self, error: F) -> Self
+    where
+        F: Fn() -> Error + Send + Sync + 'static,
+    {
+        self.stream_error = Some(Arc::new(error));
+        self
+    }
+
+    /// Waits for `delay` before answering the request.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Waits for `delay` before sending each chunk of a streamed response.
+    pub fn chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = Some(delay);
+        self
+    }
 }
 
 impl MockTransport {
@@ -39,33 +269,194 @@ impl MockTransport {
         Self::default()
     }
 
+    /// Adds a route. Routes are matched in the order they were added.
+    pub fn with_route(self, route: MockRoute) -> Self {
+        self.state.lock().unwrap().routes.push(route);
+        self
+    }
+
     /// Configures the mock to return a specific sequence of [`ChatStreamEvent`]s
     /// for streaming chat requests (`/api/chat`).
+    ///
+    /// Events are sent the way the Ollama server would send them: messages as JSON
+    /// lines, errors as `{"error": ...}` lines and partials and malformed line errors as
+    /// their raw content.
     pub fn with_chat_stream_events(self, events: Vec<ChatStreamEvent>) -> Self {
-        *self.chat_stream_events.lock().unwrap() = events;
-        self
+        let lines = events.into_iter().map(|event| match event {
+            ChatStreamEvent::Message(response) => {
+                serde_json::to_string(&response).expect("chat responses are always serializable")
+            }
+            ChatStreamEvent::Error(error) => serde_json::to_string(&OllamaError { error })
+                .expect("errors are always serializable"),
+            ChatStreamEvent::Partial { partial, .. } => partial,
+            ChatStreamEvent::LineError(error) if error.is_server_error() => {
+                let error = error.message().to_string();
+                serde_json::to_string(&OllamaError { error })
+                    .expect("errors are always serializable")
+            }
+            ChatStreamEvent::LineError(error) => String::from_utf8_lossy(error.raw()).into_owned(),
+        });
+        self.with_route(
+            MockRoute::post("/api/chat")
+                .streaming(true)
+                .respond(MockResponse::ndjson(lines)),
+        )
     }
 
     /// Configures the mock to return a specific sequence of raw `Bytes`
     /// for streaming generate requests (`/api/generate`).
     pub fn with_generate_stream_bytes(self, bytes: Vec<Bytes>) -> Self {
-        *self.generate_stream_bytes.lock().unwrap() = bytes;
-        self
+        self.with_route(
+            MockRoute::post("/api/generate")
+                .streaming(true)
+                .respond(MockResponse::chunks(bytes)),
+        )
     }
 
     /// Configures the mock to return a specific sequence of raw JSON strings
     /// for streaming chat requests (`/api/chat`). Each string will be treated
     /// as a separate line in the stream.
     pub fn with_raw_chat_stream_strings(self, strings: Vec<String>) -> Self {
-        *self.raw_chat_stream_strings.lock().unwrap() = strings;
-        self
+        self.with_route(
+            MockRoute::post("/api/chat")
+                .streaming(true)
+                .respond(MockResponse::ndjson(strings)),
+        )
     }
 
     /// Configures the mock to return a specific [`HttpResponse`]
-    /// for the next non-streaming HTTP request.
+    /// for the next non-streaming HTTP request, regardless of its path.
     pub fn with_non_streaming_http_response(self, response: HttpResponse) -> Self {
-        *self.non_streaming_http_response.lock().unwrap() = Some(response);
-        self
+        let mut mock_response =
+            MockResponse::body(response.body.unwrap_or_default()).status(response.status);
+        mock_response.headers = response.headers;
+        self.with_route(MockRoute::any().streaming(false).respond(mock_response))
+    }
+
+    /// Returns all requests received so far, in order.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Returns the requests received so far for `path`, in order.
+    pub fn requests_to(&self, path: &str) -> Vec<CapturedRequest> {
+        self.state
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the number of requests received so far for `path`.
+    pub fn call_count(&self, path: &str) -> usize {
+        self.requests_to(path).len()
+    }
+
+    /// Forgets all captured requests. Routes are left untouched.
+    pub fn clear_requests(&self) {
+        self.state.lock().unwrap().requests.clear();
+    }
+
+    /// Asserts that at least one request was sent to `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the list of captured requests if no request was sent to `path`.
+    #[track_caller]
+    pub fn assert_called(&self, path: &str) {
+        self.assert_called_with(path, |_| true);
+    }
+
+    /// Asserts that at least one request was sent to `path` with a body satisfying `predicate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the list of captured requests if no such request was sent.
+    #[track_caller]
+    pub fn assert_called_with<F>(&self, path: &str, predicate: F)
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let requests = self.requests();
+        if !requests
+            .iter()
+            .any(|request| request.path == path && predicate(&request.body))
+        {
+            panic!(
+                "expected a matching request to {}, captured requests:\n{}",
+                path,
+                CapturedRequests(&requests)
+            );
+        }
+    }
+
+    /// Asserts that no request was sent to `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the list of captured requests if a request was sent to `path`.
+    #[track_caller]
+    pub fn assert_not_called(&self, path: &str) {
+        let requests = self.requests();
+        if requests.iter().any(|request| request.path == path) {
+            panic!(
+                "expected no request to {}, captured requests:\n{}",
+                path,
+                CapturedRequests(&requests)
+            );
+        }
+    }
+
+    /// Records the request and pops the response of the first matching route.
+    fn respond_to(&self, request: HttpRequest, streaming: bool) -> Result<MockResponse> {
+        let captured = CapturedRequest {
+            body: request.json_body().unwrap_or(Value::Null),
+            verb: request.verb,
+            path: request.url,
+            headers: request.headers,
+            streaming,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let response = state
+            .routes
+            .iter_mut()
+            .find(|route| route.matches(&captured))
+            .and_then(|route| route.responses.pop_front())
+            .ok_or_else(|| {
+                Error::Client(format!(
+                    "No mock route matches {} {} with body {}",
+                    captured.verb.as_str(),
+                    captured.path,
+                    captured.body
+                ))
+            });
+        state.requests.push(captured);
+        response
+    }
+}
+
+/// Formats captured requests one per line for assertion messages.
+struct CapturedRequests<'a>(&'a [CapturedRequest]);
+
+impl fmt::Display for CapturedRequests<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "  <none>");
+        }
+        for request in self.0 {
+            writeln!(
+                f,
+                "  {} {} {}",
+                request.verb.as_str(),
+                request.path,
+                request.body
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -73,75 +464,65 @@ impl MockTransport {
 impl Transport for MockTransport {
     /// Mocks sending a non-streaming HTTP request.
     ///
-    /// If a `non_streaming_http_response` has been configured, it will be returned.
-    /// Otherwise, an empty [`HttpResponse`] is returned.
-    #[cfg_attr(feature = "tracing", instrument(skip(self, _request)))]
-    async fn send_http_request(&self, _request: HttpRequest) -> Result<HttpResponse> {
-        if let Some(response) = self.non_streaming_http_response.lock().unwrap().take() {
-            Ok(response)
-        } else {
-            // Default empty response if no mock is configured
-            Ok(HttpResponse { body: None })
+    /// The request is captured and answered with the next response of the first matching route.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let response = self.respond_to(request, false)?;
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let body = match response.body {
+            MockBody::Full(bytes) => bytes,
+            MockBody::Chunks(chunks) => chunks.concat().into(),
+            MockBody::Error(error) => return Err(error()),
+        };
+        if !response.status.is_success() {
+            return Err(status_error(response.status, &body));
         }
+        if let Some(error) = response.stream_error {
+            return Err(error());
+        }
+
+        Ok(HttpResponse {
+            status: response.status,
+            headers: response.headers,
+            body: Some(body),
+            ..Default::default()
+        })
     }
 
     /// Mocks sending a streaming HTTP request.
     ///
-    /// Depending on the request URL and configured mock data:
-    /// - For `/api/chat`, it returns a stream of serialized [`ChatStreamEvent`]s
-    ///   or raw JSON strings if `raw_chat_stream_strings` is set.
-    /// - For `/api/generate`, it returns a stream of raw `Bytes` if `generate_stream_bytes` is set.
-    /// If no relevant mock data is found, an empty stream is returned.
+    /// The request is captured and answered with the next response of the first matching route,
+    /// honouring its chunk delays and trailing stream error.
     #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
-    async fn send_http_stream_request(
-        &self,
-        request: HttpRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>> {
-        if request.url == "/api/chat" {
-            let raw_responses = self
-                .raw_chat_stream_strings
-                .lock()
-                .unwrap()
-                .drain(..)
-                .collect::<Vec<_>>();
-            if !raw_responses.is_empty() {
-                let byte_stream = stream::iter(raw_responses)
-                    .map(|s| Ok(Bytes::from(format!("{}\n", s))))
-                    .boxed();
-                return Ok(byte_stream);
-            }
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let response = self.respond_to(request, true)?;
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
 
-            let chat_events = self
-                .chat_stream_events
-                .lock()
-                .unwrap()
-                .drain(..)
-                .collect::<Vec<_>>();
-            if !chat_events.is_empty() {
-                let byte_stream = stream::iter(chat_events)
-                    .map(|event| {
-                        let json_string = serde_json::to_string(&event).map_err(|e| {
-                            Error::Protocol(format!("Failed to serialize mock event: {}", e))
-                        })?;
-                        Ok(Bytes::from(format!("{}\n", json_string)))
-                    })
-                    .boxed();
-                return Ok(byte_stream);
-            }
-        } else if request.url == "/api/generate" {
-            let generate_bytes = self
-                .generate_stream_bytes
-                .lock()
-                .unwrap()
-                .drain(..)
-                .collect::<Vec<_>>();
-            if !generate_bytes.is_empty() {
-                let byte_stream = stream::iter(generate_bytes).map(Ok).boxed();
-                return Ok(byte_stream);
-            }
+        let chunks = match response.body {
+            MockBody::Full(bytes) => vec![bytes],
+            MockBody::Chunks(chunks) => chunks,
+            MockBody::Error(error) => return Err(error()),
+        };
+        if !response.status.is_success() {
+            return Err(status_error(response.status, &chunks.concat()));
         }
+        let chunk_delay = response.chunk_delay;
+        let chunk_stream = stream::iter(chunks).then(move |chunk| async move {
+            if let Some(delay) = chunk_delay {
+                tokio::time::sleep(delay).await;
+            }
+            Ok(chunk)
+        });
+        let error_stream = stream::iter(response.stream_error.map(|error| Err(error())));
 
-        // Default empty stream if no mock is configured for the given request
-        Ok(stream::empty().boxed())
+        let mut stream_response = HttpStreamResponse::new(chunk_stream.chain(error_stream).boxed());
+        stream_response.status = response.status;
+        stream_response.headers = response.headers;
+        Ok(stream_response)
     }
 }
@@ -3,20 +3,43 @@
 //! This module provides an abstraction layer for sending HTTP requests, allowing
 //! different underlying HTTP clients or mock implementations to be used.
 
-use std::pin::Pin;
-
 use async_trait::async_trait;
-use bytes::Bytes;
-use futures::Stream;
+use http::StatusCode;
 
-use crate::types::{HttpRequest, HttpResponse};
-use crate::Result;
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse, OllamaError};
+use crate::{Error, Result};
 
+mod cassette;
+mod chaos_transport;
+mod circuit_breaker_transport;
+#[cfg(any(feature = "hyper", all(unix, feature = "unix-socket")))]
+mod hyper_common;
+#[cfg(feature = "hyper")]
+mod hyper_transport;
 mod mock_transport;
+mod pool_transport;
+mod recording_transport;
+mod replay_transport;
+#[cfg(feature = "reqwest")]
 mod reqwest_transport;
+#[cfg(all(unix, feature = "unix-socket"))]
+mod unix_socket_transport;
 
-pub use mock_transport::MockTransport;
+pub use cassette::{
+    Cassette, Interaction, RecordedBytes, RecordedChunk, RecordedRequest, RecordedResponse,
+};
+pub use chaos_transport::{ChaosFault, ChaosTransport};
+pub use circuit_breaker_transport::{CircuitBreakerTransport, CircuitState};
+#[cfg(feature = "hyper")]
+pub use hyper_transport::HyperTransport;
+pub use mock_transport::{CapturedRequest, MockResponse, MockRoute, MockTransport};
+pub use pool_transport::{PoolHostStatus, PoolStrategy, PoolTransport};
+pub use recording_transport::RecordingTransport;
+pub use replay_transport::ReplayTransport;
+#[cfg(feature = "reqwest")]
 pub use reqwest_transport::ReqwestTransport;
+#[cfg(all(unix, feature = "unix-socket"))]
+pub use unix_socket_transport::UnixSocketTransport;
 
 /// A trait for sending HTTP requests to the Ollama API.
 ///
@@ -34,7 +57,7 @@ pub trait Transport: Send + Sync + 'static {
     /// Returns an [`Error`](enum@crate::Error) if the request fails or the response cannot be processed.
     async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse>;
 
-    /// Sends a streaming HTTP request and returns a stream of response bytes.
+    /// Sends a streaming HTTP request and returns the response head along with a stream of body bytes.
     ///
     /// This is used for API endpoints that return a continuous stream of data,
     /// such as chat completions or text generation.
@@ -46,8 +69,45 @@ pub trait Transport: Send + Sync + 'static {
     /// # Errors
     ///
     /// Returns an [`Error`](enum@crate::Error) if the request fails or the stream cannot be established.
-    async fn send_http_stream_request(
-        &self,
-        request: HttpRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>>;
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse>;
+}
+
+/// Runs `future` to completion, failing with an [`Error::Transport`](crate::Error::Transport)
+/// if it does not finish within `timeout`.
+#[cfg(any(
+    feature = "reqwest",
+    feature = "hyper",
+    all(unix, feature = "unix-socket")
+))]
+pub(crate) async fn with_timeout<T, F>(timeout: Option<std::time::Duration>, future: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?,
+        None => future.await,
+    }
+}
+
+/// Builds the [`Error::Http`] for a response with a non-success `status` and `body`.
+pub(crate) fn status_error(status: StatusCode, body: &[u8]) -> Error {
+    let message = serde_json::from_slice::<OllamaError>(body)
+        .map(|err| err.error)
+        .unwrap_or_else(|_| String::from_utf8_lossy(body).into_owned());
+    Error::Http { status, message }
+}
+
+/// Returns `true` for errors that indicate an unhealthy server rather than a bad request.
+///
+/// These are connection errors and timeouts, which transports report as
+/// [`Error::Transport`], and responses with a 5xx status. Responses with a 4xx status and
+/// errors reported by the model in a successful response do not count.
+pub(crate) fn is_server_failure(error: &Error) -> bool {
+    match error {
+        Error::Transport(_) => true,
+        Error::Http { status, .. } => status.is_server_error(),
+        _ => false,
+    }
 }
@@ -0,0 +1,495 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "metrics")]
+use metrics::counter;
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Deserialize;
+
+use crate::builder::http_transport;
+use crate::transport::{is_server_failure, Transport};
+use crate::types::{
+    HttpRequest, HttpResponse, HttpStreamResponse, HttpVerb, ListRunningModelsResponse,
+};
+use crate::{Error, Result};
+
+/// How a [`PoolTransport`] picks the host for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolStrategy {
+    /// Cycles through the healthy hosts in order.
+    #[default]
+    RoundRobin,
+    /// Picks the healthy host with the fewest requests in flight.
+    LeastInFlight,
+    /// Prefers healthy hosts where the requested model is already loaded, as reported
+    /// by `/api/ps`, and falls back to the host with the fewest requests in flight.
+    ///
+    /// A single request at a time refreshes the running models of a host once they are
+    /// older than [`affinity_ttl`](PoolTransport::affinity_ttl), waiting at most
+    /// [`affinity_timeout`](PoolTransport::affinity_timeout) for the answer. Other
+    /// requests use the models cached so far. A failed or timed out `/api/ps` request
+    /// counts as a failure of the host.
+    ModelAffinity,
+}
+
+/// A snapshot of the state of a host in a [`PoolTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolHostStatus {
+    /// The name the host was added with.
+    pub name: String,
+    /// The number of requests (including open streams) currently in flight on the host.
+    pub in_flight: usize,
+    /// The number of consecutive failed requests.
+    pub consecutive_failures: u32,
+    /// Whether the host is currently ejected from the pool.
+    pub ejected: bool,
+}
+
+/// A [`Transport`] that spreads requests over several Ollama hosts.
+///
+/// Hosts are tracked passively: a host is ejected from the pool after
+/// [`failure_threshold`](PoolTransport::failure_threshold) consecutive failed
+/// requests, and re-admitted once the [`cooldown`](PoolTransport::cooldown) has
+/// elapsed. A single failure after re-admission ejects it again. Only connection
+/// errors, timeouts and responses with a 5xx status count as failures, including
+/// errors in an open stream; a response with a 4xx status means the host is healthy.
+/// If every host is ejected, the host that will be re-admitted first is used rather
+/// than failing the request.
+///
+/// ```no_run
+/// use ollama_sdk::transport::{PoolStrategy, PoolTransport};
+///
+/// # fn main() -> ollama_sdk::Result<()> {
+/// let pool = PoolTransport::new(PoolStrategy::ModelAffinity)
+///     .with_base_url("http://gpu-1:11434", None)?
+///     .with_base_url("http://gpu-2:11434", None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PoolTransport {
+    hosts: Vec<Arc<PoolHost>>,
+    strategy: PoolStrategy,
+    next: AtomicUsize,
+    failure_threshold: u32,
+    cooldown: Duration,
+    affinity_ttl: Duration,
+    affinity_timeout: Duration,
+}
+
+/// A host in the pool and its passive health state.
+struct PoolHost {
+    name: String,
+    transport: Arc<dyn Transport + Send + Sync>,
+    in_flight: AtomicUsize,
+    health: Mutex<HostHealth>,
+    running_models: Mutex<Option<RunningModels>>,
+    refreshing: AtomicBool,
+}
+
+#[derive(Default)]
+struct HostHealth {
+    consecutive_failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+/// The models loaded on a host, as last reported by `/api/ps`.
+struct RunningModels {
+    fetched_at: Instant,
+    models: Vec<String>,
+}
+
+impl PoolHost {
+    fn is_available(&self, now: Instant) -> bool {
+        self.health
+            .lock()
+            .unwrap()
+            .ejected_until
+            .map_or(true, |until| until <= now)
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures = 0;
+        health.ejected_until = None;
+    }
+
+    fn record_failure(&self, threshold: u32, cooldown: Duration) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        let readmitted = health.ejected_until.is_some();
+        if readmitted || health.consecutive_failures >= threshold {
+            health.ejected_until = Some(Instant::now() + cooldown);
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                host = %self.name,
+                failures = health.consecutive_failures,
+                "ejecting host from pool"
+            );
+            #[cfg(feature = "metrics")]
+            counter!("ollama_client.pool_host_ejections_total", "host" => self.name.clone())
+                .increment(1);
+        }
+    }
+
+    fn has_model(&self, model: &str) -> bool {
+        self.running_models
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|running| running.models.iter().any(|m| m == model))
+    }
+
+    /// Refreshes the list of running models if it is older than `ttl`, waiting at most
+    /// `timeout` for the host to answer.
+    ///
+    /// Returns right away if another request is already refreshing the list. A failure
+    /// is recorded against the health of the host, and the models fetched before are
+    /// kept until the next refresh.
+    async fn refresh_running_models(&self, pool: &PoolTransport) {
+        let fresh = |running: &Option<RunningModels>| {
+            running
+                .as_ref()
+                .is_some_and(|running| running.fetched_at.elapsed() < pool.affinity_ttl)
+        };
+        if fresh(&self.running_models.lock().unwrap()) {
+            return;
+        }
+        let Some(_refresh) = RefreshGuard::acquire(self) else {
+            return;
+        };
+        // Another request may have finished a refresh in the meantime.
+        if fresh(&self.running_models.lock().unwrap()) {
+            return;
+        }
+
+        let request = self
+            .transport
+            .send_http_request(HttpRequest::new("/api/ps"));
+        let result = match tokio::time::timeout(pool.affinity_timeout, request).await {
+            Ok(response) => response.and_then(|response| {
+                ListRunningModelsResponse::from_bytes(response.body.unwrap_or_default())
+            }),
+            Err(_) => Err(Error::Transport(
+                format!("/api/ps did not answer within {:?}", pool.affinity_timeout).into(),
+            )),
+        };
+
+        let mut running = self.running_models.lock().unwrap();
+        let models = match result {
+            Ok(response) => {
+                self.record_success();
+                response.models.into_iter().map(|m| m.model).collect()
+            }
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(host = %self.name, error = %e, "failed to list running models");
+                if is_server_failure(&e) {
+                    self.record_failure(pool.failure_threshold, pool.cooldown);
+                }
+                running
+                    .take()
+                    .map(|running| running.models)
+                    .unwrap_or_default()
+            }
+        };
+        *running = Some(RunningModels {
+            fetched_at: Instant::now(),
+            models,
+        });
+    }
+
+    /// Remembers that `model` is loaded on this host after it served a request for it.
+    fn mark_model_loaded(&self, model: &str) {
+        if let Some(running) = self.running_models.lock().unwrap().as_mut() {
+            if !running.models.iter().any(|m| m == model) {
+                running.models.push(model.to_string());
+            }
+        }
+    }
+}
+
+/// Marks a host as refreshing its running models until dropped, so that a single
+/// request refreshes them at a time.
+struct RefreshGuard<'a>(&'a PoolHost);
+
+impl<'a> RefreshGuard<'a> {
+    fn acquire(host: &'a PoolHost) -> Option<Self> {
+        host.refreshing
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self(host))
+    }
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        self.0.refreshing.store(false, Ordering::Release);
+    }
+}
+
+/// Decrements the in-flight count of a host when dropped.
+struct InFlightGuard(Arc<PoolHost>);
+
+impl InFlightGuard {
+    fn new(host: Arc<PoolHost>) -> Self {
+        host.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(host)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl PoolTransport {
+    /// Creates an empty [`PoolTransport`] using the given strategy.
+    ///
+    /// By default a host is ejected after 3 consecutive failures for a cooldown of
+    /// 30 seconds, and running models are re-fetched at most every 10 seconds, waiting
+    /// at most 1 second for them.
+    pub fn new(strategy: PoolStrategy) -> Self {
+        Self {
+            hosts: Vec::new(),
+            strategy,
+            next: AtomicUsize::new(0),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+            affinity_ttl: Duration::from_secs(10),
+            affinity_timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Adds a host reached through `transport`, identified by `name` in logs and status.
+    pub fn with_host(
+        mut self,
+        name: impl Into<String>,
+        transport: Arc<dyn Transport + Send + Sync>,
+    ) -> Self {
+        self.hosts.push(Arc::new(PoolHost {
+            name: name.into(),
+            transport,
+            in_flight: AtomicUsize::new(0),
+            health: Mutex::new(HostHealth::default()),
+            running_models: Mutex::new(None),
+            refreshing: AtomicBool::new(false),
+        }));
+        self
+    }
+
+    /// Adds a host reached over HTTP at `base_url`.
+    ///
+    /// The host uses [`ReqwestTransport`](crate::transport::ReqwestTransport) with the
+    /// `reqwest` feature, or `HyperTransport` with only the `hyper` feature enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the Ollama server.
+    /// * `api_key` - An optional API key for authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`] if the URL is invalid, the transport cannot be built, or
+    /// no HTTP transport feature is enabled.
+    pub fn with_base_url(self, base_url: &str, api_key: Option<String>) -> Result<Self> {
+        let transport = http_transport(base_url, api_key)?;
+        Ok(self.with_host(base_url, transport))
+    }
+
+    /// Sets the number of consecutive failures after which a host is ejected. Defaults to 3.
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Sets how long an ejected host stays out of the pool. Defaults to 30 seconds.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets how long the running models of a host are cached for
+    /// [`PoolStrategy::ModelAffinity`]. Defaults to 10 seconds.
+    pub fn affinity_ttl(mut self, affinity_ttl: Duration) -> Self {
+        self.affinity_ttl = affinity_ttl;
+        self
+    }
+
+    /// Sets how long a request waits for `/api/ps` when refreshing the running models of
+    /// a host for [`PoolStrategy::ModelAffinity`]. Defaults to 1 second.
+    pub fn affinity_timeout(mut self, affinity_timeout: Duration) -> Self {
+        self.affinity_timeout = affinity_timeout;
+        self
+    }
+
+    /// Returns a snapshot of the state of every host, in the order they were added.
+    pub fn status(&self) -> Vec<PoolHostStatus> {
+        let now = Instant::now();
+        self.hosts
+            .iter()
+            .map(|host| {
+                let health = host.health.lock().unwrap();
+                PoolHostStatus {
+                    name: host.name.clone(),
+                    in_flight: host.in_flight.load(Ordering::SeqCst),
+                    consecutive_failures: health.consecutive_failures,
+                    ejected: health.ejected_until.is_some_and(|until| until > now),
+                }
+            })
+            .collect()
+    }
+
+    /// Picks the host for a request running `model` according to the strategy and host health.
+    async fn select_host(&self, model: Option<&str>) -> Result<Arc<PoolHost>> {
+        if self.hosts.is_empty() {
+            return Err(Error::Client("PoolTransport has no hosts".into()));
+        }
+
+        let now = Instant::now();
+        let available: Vec<&Arc<PoolHost>> = self
+            .hosts
+            .iter()
+            .filter(|host| host.is_available(now))
+            .collect();
+        if available.is_empty() {
+            let host = self
+                .hosts
+                .iter()
+                .min_by_key(|host| host.health.lock().unwrap().ejected_until)
+                .expect("pool is not empty");
+            return Ok(host.clone());
+        }
+
+        let least_in_flight = |hosts: &[&Arc<PoolHost>]| {
+            hosts
+                .iter()
+                .min_by_key(|host| host.in_flight.load(Ordering::SeqCst))
+                .map(|host| Arc::clone(host))
+        };
+
+        let host = match self.strategy {
+            PoolStrategy::RoundRobin => {
+                let index = self.next.fetch_add(1, Ordering::Relaxed) % available.len();
+                Some(available[index].clone())
+            }
+            PoolStrategy::LeastInFlight => least_in_flight(&available),
+            PoolStrategy::ModelAffinity => match model {
+                Some(model) => {
+                    futures::future::join_all(
+                        available
+                            .iter()
+                            .map(|host| host.refresh_running_models(self)),
+                    )
+                    .await;
+                    // A failed refresh may have ejected a host.
+                    let now = Instant::now();
+                    let healthy: Vec<&Arc<PoolHost>> = available
+                        .iter()
+                        .copied()
+                        .filter(|host| host.is_available(now))
+                        .collect();
+                    let candidates = if healthy.is_empty() {
+                        &available
+                    } else {
+                        &healthy
+                    };
+                    let loaded: Vec<&Arc<PoolHost>> = candidates
+                        .iter()
+                        .copied()
+                        .filter(|host| host.has_model(model))
+                        .collect();
+                    least_in_flight(&loaded).or_else(|| least_in_flight(candidates))
+                }
+                None => least_in_flight(&available),
+            },
+        };
+
+        Ok(host.expect("available hosts are not empty"))
+    }
+
+    /// Updates the health of `host` with the outcome of a request for `model`.
+    fn record<T>(&self, host: &PoolHost, model: Option<&str>, result: &Result<T>) {
+        match result {
+            Ok(_) => {
+                host.record_success();
+                if let Some(model) = model {
+                    host.mark_model_loaded(model);
+                }
+            }
+            Err(e) if is_server_failure(e) => {
+                host.record_failure(self.failure_threshold, self.cooldown)
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// The part of a request body that names the model to run.
+#[derive(Deserialize)]
+struct ModelField {
+    model: Option<String>,
+}
+
+/// Returns the `model` field of a request body, for requests that run a model.
+///
+/// The body is only parsed for [`PoolStrategy::ModelAffinity`], the only strategy that
+/// uses the model.
+fn request_model(strategy: PoolStrategy, request: &HttpRequest) -> Option<String> {
+    if strategy != PoolStrategy::ModelAffinity || request.verb != HttpVerb::POST {
+        return None;
+    }
+    serde_json::from_slice::<ModelField>(request.body.as_deref()?)
+        .ok()?
+        .model
+}
+
+#[async_trait]
+impl Transport for PoolTransport {
+    /// Sends the request to a host picked by the pool strategy.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let model = request_model(self.strategy, &request);
+        let host = self.select_host(model.as_deref()).await?;
+        let _guard = InFlightGuard::new(host.clone());
+
+        let result = host.transport.send_http_request(request).await;
+        self.record(&host, model.as_deref(), &result);
+        result
+    }
+
+    /// Sends the streaming request to a host picked by the pool strategy.
+    ///
+    /// The request counts as in flight until the returned stream is dropped, and an
+    /// error in the stream counts as a failure of the host.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let model = request_model(self.strategy, &request);
+        let host = self.select_host(model.as_deref()).await?;
+        let guard = InFlightGuard::new(host.clone());
+
+        let result = host.transport.send_http_stream_request(request).await;
+        self.record(&host, model.as_deref(), &result);
+        let mut response = result?;
+
+        let (threshold, cooldown) = (self.failure_threshold, self.cooldown);
+        response.body = response
+            .body
+            .inspect(move |item| {
+                if let Err(e) = item {
+                    if is_server_failure(e) {
+                        guard.0.record_failure(threshold, cooldown);
+                    }
+                }
+            })
+            .boxed();
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+
+use crate::transport::cassette::record_headers;
+use crate::transport::{
+    Cassette, Interaction, RecordedBytes, RecordedChunk, RecordedRequest, RecordedResponse,
+    Transport,
+};
+use crate::types::{ByteStream, HttpRequest, HttpResponse, HttpStreamResponse, OllamaError};
+use crate::{Error, Result};
+
+/// A [`Transport`] wrapper that records every interaction of the wrapped transport
+/// into a [`Cassette`] file.
+///
+/// Interactions are collected in memory as they complete, and written to the cassette
+/// file by [`RecordingTransport::save`], so that no file I/O blocks the requests. The
+/// cassette can later be served by [`ReplayTransport`](crate::transport::ReplayTransport)
+/// without a running Ollama server. Streaming responses are recorded chunk by chunk,
+/// together with the delay between chunks, and marked as
+/// [`truncated`](RecordedResponse::truncated) if they are dropped before they end.
+/// Responses with a non-success status are recorded with their status and error
+/// message, so that they fail the same way when replayed. Requests that fail without a
+/// response, e.g. because the connection was refused, are not recorded.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport + Send + Sync>,
+    recorder: Arc<Recorder>,
+}
+
+/// The cassette being recorded and the file it is saved to.
+struct Recorder {
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    /// Appends an interaction to the cassette.
+    fn record(&self, interaction: Interaction) {
+        self.cassette.lock().unwrap().interactions.push(interaction);
+    }
+
+    /// Appends the interaction of a request that failed with `error`, if the server
+    /// answered it with a non-success status.
+    ///
+    /// The body is recorded as an Ollama error object, which replays to the same
+    /// [`Error::Http`].
+    fn record_failure(&self, request: RecordedRequest, error: &Error) {
+        let Error::Http { status, message } = error else {
+            return;
+        };
+        let body = serde_json::to_vec(&OllamaError {
+            error: message.clone(),
+        })
+        .expect("errors are always serializable");
+        self.record(Interaction {
+            request,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: Default::default(),
+                body: Some(RecordedBytes::from(body.as_slice())),
+                chunks: None,
+                error: None,
+                truncated: false,
+            },
+        });
+    }
+}
+
+impl RecordingTransport {
+    /// Creates a new [`RecordingTransport`] wrapping `inner` and writing to the cassette at `path`.
+    ///
+    /// Any existing cassette at `path` is overwritten by [`RecordingTransport::save`].
+    pub fn new(inner: Arc<dyn Transport + Send + Sync>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            recorder: Arc::new(Recorder {
+                path: path.into(),
+                cassette: Mutex::new(Cassette::default()),
+            }),
+        }
+    }
+
+    /// Returns the path of the cassette file.
+    pub fn path(&self) -> &Path {
+        &self.recorder.path
+    }
+
+    /// Returns a snapshot of the interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.recorder.cassette.lock().unwrap().clone()
+    }
+
+    /// Writes the interactions recorded so far to the cassette file.
+    ///
+    /// Call this once recording is done. Streams that are still open are not part of
+    /// the cassette yet. The file is written with blocking I/O, so call it from
+    /// [`tokio::task::spawn_blocking`] when other tasks must keep running meanwhile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`](variant@crate::Error::Client) if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.recorder
+            .cassette
+            .lock()
+            .unwrap()
+            .save(&self.recorder.path)
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    /// Forwards the request to the wrapped transport and records the response.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let recorded_request = RecordedRequest::from(&request);
+        let response = match self.inner.send_http_request(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.recorder.record_failure(recorded_request, &e);
+                return Err(e);
+            }
+        };
+
+        self.recorder.record(Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                headers: record_headers(&response.headers),
+                body: Some(RecordedBytes::from(
+                    response.body.as_deref().unwrap_or_default(),
+                )),
+                chunks: None,
+                error: None,
+                truncated: false,
+            },
+        });
+
+        Ok(response)
+    }
+
+    /// Forwards the request to the wrapped transport and records the stream as it is consumed.
+    ///
+    /// The interaction is recorded once the stream ends, fails, or is dropped.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let recorded_request = RecordedRequest::from(&request);
+        let mut response = match self.inner.send_http_stream_request(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.recorder.record_failure(recorded_request, &e);
+                return Err(e);
+            }
+        };
+
+        let recording = RecordingStream {
+            inner: response.body,
+            pending: Some(Interaction {
+                request: recorded_request,
+                response: RecordedResponse {
+                    status: response.status.as_u16(),
+                    headers: record_headers(&response.headers),
+                    body: None,
+                    chunks: Some(Vec::new()),
+                    error: None,
+                    truncated: false,
+                },
+            }),
+            last_chunk_at: Instant::now(),
+            recorder: self.recorder.clone(),
+        };
+        response.body = Box::pin(recording);
+
+        Ok(response)
+    }
+}
+
+/// Records chunks as they pass through and hands the interaction to the [`Recorder`] when done.
+struct RecordingStream {
+    inner: ByteStream,
+    pending: Option<Interaction>,
+    last_chunk_at: Instant,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingStream {
+    fn finish(&mut self) {
+        if let Some(interaction) = self.pending.take() {
+            self.recorder.record(interaction);
+        }
+    }
+}
+
+impl Stream for RecordingStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let item = futures::ready!(this.inner.as_mut().poll_next(cx));
+
+        match &item {
+            Some(Ok(bytes)) => {
+                let now = Instant::now();
+                let delay_ms = now.duration_since(this.last_chunk_at).as_millis() as u64;
+                this.last_chunk_at = now;
+                if let Some(chunks) = this
+                    .pending
+                    .as_mut()
+                    .and_then(|interaction| interaction.response.chunks.as_mut())
+                {
+                    chunks.push(RecordedChunk {
+                        delay_ms,
+                        data: RecordedBytes::from(bytes.as_ref()),
+                    });
+                }
+            }
+            Some(Err(e)) => {
+                if let Some(interaction) = this.pending.as_mut() {
+                    interaction.response.error = Some(e.to_string());
+                }
+                this.finish();
+            }
+            None => this.finish(),
+        }
+
+        Poll::Ready(item)
+    }
+}
+
+impl Drop for RecordingStream {
+    fn drop(&mut self) {
+        if let Some(interaction) = self.pending.as_mut() {
+            interaction.response.truncated = true;
+        }
+        self.finish();
+    }
+}
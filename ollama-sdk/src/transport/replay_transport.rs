@@ -0,0 +1,174 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use http::header::{HeaderName, HeaderValue};
+use http::{HeaderMap, StatusCode};
+
+use crate::transport::{status_error, Cassette, Interaction, RecordedResponse, Transport};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse};
+use crate::{Error, Result};
+
+/// A [`Transport`] that serves responses from a [`Cassette`] instead of a real server.
+///
+/// Each incoming request is matched against the recorded interactions by method,
+/// path and JSON body (ignoring key order and whitespace). Every interaction is served
+/// at most once, in recording order, so repeated identical requests replay their
+/// recorded responses in sequence. Requests without a matching interaction fail with
+/// an [`Error::Client`] describing the request.
+pub struct ReplayTransport {
+    interactions: Mutex<Vec<Option<Interaction>>>,
+    replay_timing: bool,
+}
+
+impl ReplayTransport {
+    /// Creates a new [`ReplayTransport`] serving the interactions of `cassette`.
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            interactions: Mutex::new(cassette.interactions.into_iter().map(Some).collect()),
+            replay_timing: false,
+        }
+    }
+
+    /// Creates a new [`ReplayTransport`] from a cassette file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cassette cannot be read or parsed. See [`Cassette::load`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Configures whether streamed chunks are delayed by their recorded delays.
+    ///
+    /// Disabled by default, so that replayed streams are delivered as fast as possible.
+    pub fn with_timing(mut self, replay_timing: bool) -> Self {
+        self.replay_timing = replay_timing;
+        self
+    }
+
+    /// Returns the number of recorded interactions that have not been served yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|interaction| interaction.is_some())
+            .count()
+    }
+
+    /// Takes the first unused interaction matching `request`.
+    fn take_interaction(&self, request: &HttpRequest) -> Result<Interaction> {
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions
+            .iter_mut()
+            .find(|slot| {
+                slot.as_ref()
+                    .is_some_and(|interaction| interaction.request.matches(request))
+            })
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                let body = request
+                    .body
+                    .as_deref()
+                    .map(|body| String::from_utf8_lossy(body).into_owned())
+                    .unwrap_or_else(|| "<empty>".to_string());
+                Error::Client(format!(
+                    "No recorded interaction matches {} {} with body {}",
+                    request.verb.as_str(),
+                    request.url,
+                    body
+                ))
+            })
+    }
+}
+
+/// Rebuilds the status code and headers of a recorded response.
+fn response_head(response: &RecordedResponse) -> Result<(StatusCode, HeaderMap)> {
+    let status = StatusCode::from_u16(response.status)
+        .map_err(|e| Error::Protocol(format!("Invalid status in cassette: {}", e)))?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &response.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::Protocol(format!("Invalid header in cassette: {}", e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| Error::Protocol(format!("Invalid header in cassette: {}", e)))?;
+        headers.insert(name, value);
+    }
+
+    Ok((status, headers))
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    /// Serves the recorded response of the first unused matching interaction.
+    ///
+    /// Responses that were recorded as streams are returned with their chunks concatenated.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let interaction = self.take_interaction(&request)?;
+        let (status, headers) = response_head(&interaction.response)?;
+        let body = interaction.response.body_bytes();
+        if !status.is_success() {
+            return Err(status_error(status, &body));
+        }
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: Some(body),
+            ..Default::default()
+        })
+    }
+
+    /// Serves the recorded chunks of the first unused matching interaction as a stream.
+    ///
+    /// If the recorded stream ended with an error, the same error message is replayed
+    /// as an [`Error::Transport`] after the last chunk. A stream that was dropped before
+    /// it ended while being recorded fails with an [`Error::Client`] after its last
+    /// recorded chunk, as the rest of the response is unknown.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let interaction = self.take_interaction(&request)?;
+        let (status, headers) = response_head(&interaction.response)?;
+        if !status.is_success() {
+            return Err(status_error(status, &interaction.response.body_bytes()));
+        }
+        let replay_timing = self.replay_timing;
+
+        let response = interaction.response;
+        let chunks = match response.chunks {
+            Some(chunks) => chunks
+                .into_iter()
+                .map(|chunk| (Duration::from_millis(chunk.delay_ms), chunk.data.to_bytes()))
+                .collect::<Vec<_>>(),
+            None => vec![(Duration::ZERO, response.body_bytes())],
+        };
+
+        let chunk_stream = stream::iter(chunks).then(move |(delay, bytes)| async move {
+            if replay_timing && !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            Ok(bytes)
+        });
+        let error = match (response.error, response.truncated) {
+            (Some(message), _) => Some(Error::Transport(message.into())),
+            (None, true) => Some(Error::Client(
+                "The recorded stream was dropped before it ended".to_string(),
+            )),
+            (None, false) => None,
+        };
+        let error_stream = stream::iter(error.map(Err));
+
+        let mut stream_response = HttpStreamResponse::new(chunk_stream.chain(error_stream).boxed());
+        stream_response.status = status;
+        stream_response.headers = headers;
+        Ok(stream_response)
+    }
+}
@@ -1,15 +1,14 @@
-use std::pin::Pin;
+use std::time::Instant;
 
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
 use async_trait::async_trait;
-use bytes::Bytes;
-use futures::{Stream, StreamExt};
+use futures::StreamExt;
 use reqwest::{Client, Url};
 
-use crate::transport::Transport;
-use crate::types::{HttpRequest, HttpResponse, HttpVerb};
+use crate::transport::{status_error, with_timeout, Transport};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse, HttpVerb, ResponseTiming};
 use crate::{Error, Result};
 
 /// A [`Transport`] implementation that uses the `reqwest` crate for making HTTP requests.
@@ -19,7 +18,8 @@ use crate::{Error, Result};
 /// and processing responses, including streaming responses.
 pub struct ReqwestTransport {
     client: Client,
-    base_url: Url,
+    /// The base URL without a trailing slash, which request paths are appended to.
+    base_url: String,
     api_key: Option<String>,
 }
 
@@ -28,7 +28,8 @@ impl ReqwestTransport {
     ///
     /// # Arguments
     ///
-    /// * `base_url` - The base URL of the Ollama server.
+    /// * `base_url` - The base URL of the Ollama server. A path, e.g. `https://gateway/ollama`,
+    ///   is kept in front of the API paths.
     /// * `api_key` - An optional API key for authentication.
     ///
     /// # Errors
@@ -40,16 +41,14 @@ impl ReqwestTransport {
             .map_err(|e| Error::Client(e.to_string()))?;
         Ok(Self {
             client,
-            base_url,
+            base_url: base_url.as_str().trim_end_matches('/').to_string(),
             api_key,
         })
     }
 
     /// Helper to build and send a reqwest request, handling common logic.
     async fn build_and_send_request(&self, request: HttpRequest) -> Result<reqwest::Response> {
-        let url = self
-            .base_url
-            .join(&request.url)
+        let url = Url::parse(&format!("{}{}", self.base_url, request.url))
             .map_err(|e| Error::Client(e.to_string()))?;
 
         let mut request_builder = match request.verb {
@@ -64,11 +63,17 @@ impl ReqwestTransport {
         }
 
         if let Some(body) = request.body {
-            request_builder = request_builder.json(&body);
+            request_builder = request_builder.body(body);
         }
 
-        let response = request_builder.send().await.map_err(Error::Transport)?;
-        response.error_for_status_ref().map_err(Error::Transport)?;
+        request_builder = request_builder.headers(request.headers);
+
+        let response = request_builder.send().await.map_err(Error::from)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.bytes().await.map_err(Error::from)?;
+            return Err(status_error(status, &body));
+        }
         Ok(response)
     }
 }
@@ -83,14 +88,30 @@ impl Transport for ReqwestTransport {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error::Transport`] if the request fails or the response cannot be read.
+    /// Returns an [`Error::Transport`] if the request fails, times out or the response cannot
+    /// be read, and an [`Error::Http`] if the server answers with a non-success status.
     #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
     async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
-        let response = self.build_and_send_request(request).await?;
-        let response_bytes = response.bytes().await.map_err(Error::Transport)?;
-        Ok(HttpResponse {
-            body: Some(response_bytes),
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        with_timeout(timeout, async {
+            let response = self.build_and_send_request(request).await?;
+            let time_to_headers = started_at.elapsed();
+            let status = response.status();
+            let headers = response.headers().clone();
+            let response_bytes = response.bytes().await.map_err(Error::from)?;
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: Some(response_bytes),
+                timing: ResponseTiming {
+                    time_to_headers,
+                    total: Some(started_at.elapsed()),
+                },
+            })
         })
+        .await
     }
 
     /// Sends a streaming HTTP request using `reqwest` and returns a stream of response bytes.
@@ -101,17 +122,29 @@ impl Transport for ReqwestTransport {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error::Transport`] if the request fails or the stream cannot be established.
+    /// Returns an [`Error::Transport`] if the request fails, times out or the stream cannot be
+    /// established, and an [`Error::Http`] if the server answers with a non-success status.
     #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
-    async fn send_http_stream_request(
-        &self,
-        request: HttpRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>> {
-        let response = self.build_and_send_request(request).await?;
-        let stream = response
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        let response = with_timeout(timeout, self.build_and_send_request(request)).await?;
+        let time_to_headers = started_at.elapsed();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
             .bytes_stream()
-            .map(|item| item.map_err(Error::Transport))
+            .map(|item| item.map_err(Error::from))
             .boxed();
-        Ok(stream)
+        Ok(HttpStreamResponse {
+            status,
+            headers,
+            body,
+            timing: ResponseTiming {
+                time_to_headers,
+                total: None,
+            },
+        })
     }
 }
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use async_trait::async_trait;
+use hyper::body::Incoming;
+use hyper::Response;
+use hyper_util::rt::TokioIo;
+use tokio::net::UnixStream;
+
+use crate::transport::hyper_common::{
+    build_request, check_status, into_http_response, into_stream_response,
+};
+use crate::transport::{with_timeout, Transport};
+use crate::types::{HttpRequest, HttpResponse, HttpStreamResponse};
+use crate::{Error, Result};
+
+/// A [`Transport`] implementation that talks HTTP/1.1 to an Ollama server over a Unix domain socket.
+///
+/// This is useful when Ollama runs on the same host and is only reachable through a socket file,
+/// so that no TCP port has to be exposed. A new connection is opened for every request.
+///
+/// It can be selected through [`OllamaClientBuilder::unix_socket`](crate::OllamaClientBuilder::unix_socket)
+/// or by setting `OLLAMA_HOST` to a `unix:///path/to/socket` URL.
+pub struct UnixSocketTransport {
+    socket_path: PathBuf,
+    api_key: Option<String>,
+}
+
+impl UnixSocketTransport {
+    /// Creates a new `UnixSocketTransport`.
+    ///
+    /// # Arguments
+    ///
+    /// * `socket_path` - The path of the Unix domain socket the Ollama server listens on.
+    /// * `api_key` - An optional API key for authentication.
+    pub fn new(socket_path: impl Into<PathBuf>, api_key: Option<String>) -> Self {
+        Self {
+            socket_path: socket_path.into(),
+            api_key,
+        }
+    }
+
+    /// Returns the path of the Unix domain socket used by this transport.
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Helper to open a connection, send the request and check the response status.
+    async fn build_and_send_request(&self, request: HttpRequest) -> Result<Response<Incoming>> {
+        let uri = request.url.clone();
+        let stream = UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?;
+
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?;
+
+        // The connection has to be driven for as long as the response body is being read.
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+
+        let hyper_request = build_request(request, &uri, "localhost", self.api_key.as_deref())?;
+
+        let response = sender
+            .send_request(hyper_request)
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?;
+
+        check_status(response).await
+    }
+}
+
+#[async_trait]
+impl Transport for UnixSocketTransport {
+    /// Sends a non-streaming HTTP request over the Unix domain socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`HttpRequest`] to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Transport`] if the socket cannot be reached, the request times out
+    /// or the response cannot be read, and an [`Error::Http`] if the server answers with a
+    /// non-success status.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        with_timeout(timeout, async {
+            let response = self.build_and_send_request(request).await?;
+            into_http_response(response, started_at).await
+        })
+        .await
+    }
+
+    /// Sends a streaming HTTP request over the Unix domain socket and returns a stream of response bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The [`HttpRequest`] to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Transport`] if the socket cannot be reached, the request times out
+    /// or the stream cannot be established, and an [`Error::Http`] if the server answers
+    /// with a non-success status.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, request)))]
+    async fn send_http_stream_request(&self, request: HttpRequest) -> Result<HttpStreamResponse> {
+        let started_at = Instant::now();
+        let timeout = request.timeout;
+
+        let response = with_timeout(timeout, self.build_and_send_request(request)).await?;
+        Ok(into_stream_response(response, started_at))
+    }
+}
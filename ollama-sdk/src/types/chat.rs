@@ -2,15 +2,22 @@
 
 use std::pin::Pin;
 
-use crate::parser::{GenericStreamParser, StreamEventExt};
+use crate::parser::{GenericStreamParser, StreamEvent, StreamLineError};
+use crate::streaming::{
+    line_failure, read_recording, replay, unparseable, write_ndjson, BroadcastConfig, Broadcaster,
+    ChatDeltaStream, ChatStreamAccumulator, PartialJsonStream, StopConditions, StopStream,
+    StreamChunk, StreamTimer, StreamTimings, Subscriber, TextReader,
+};
 use crate::types::Thinking;
-use crate::Result;
+use crate::{Error, Result};
 use bytes::Bytes;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use ollama_sdk_macros::FromBytes;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
 
-use super::{Role, ThinkingLevel};
+use super::{ResponseStats, Role, ThinkingLevel};
 
 /// Represents a chat request to the Ollama API.
 ///
@@ -143,6 +150,43 @@ pub struct ChatResponse {
     pub message: ChatResponseMessage,
     /// Indicates if the chat completion is complete.
     pub done: bool,
+    /// The reason why the chat completion finished (e.g., "stop", "length").
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    /// The total duration of the chat completion in nanoseconds.
+    #[serde(default)]
+    pub total_duration: u64,
+    /// The duration spent loading the model in nanoseconds.
+    #[serde(default)]
+    pub load_duration: u64,
+    /// The number of tokens in the prompt that were evaluated.
+    #[serde(default)]
+    pub prompt_eval_count: u64,
+    /// The duration spent evaluating the prompt in nanoseconds.
+    #[serde(default)]
+    pub prompt_eval_duration: u64,
+    /// The number of tokens generated.
+    #[serde(default)]
+    pub eval_count: u64,
+    /// The duration spent generating tokens in nanoseconds.
+    #[serde(default)]
+    pub eval_duration: u64,
+}
+
+impl ChatResponse {
+    /// Returns the timing and token statistics of the response.
+    ///
+    /// These are only reported with the final chunk of a streamed response.
+    pub fn stats(&self) -> ResponseStats {
+        ResponseStats {
+            total_duration: self.total_duration,
+            load_duration: self.load_duration,
+            prompt_eval_count: self.prompt_eval_count,
+            prompt_eval_duration: self.prompt_eval_duration,
+            eval_count: self.eval_count,
+            eval_duration: self.eval_duration,
+        }
+    }
 }
 
 /// Represents a single message in a chat response.
@@ -335,24 +379,46 @@ impl From<StreamingChatRequest> for ChatRequest {
 }
 
 /// Represents an event received from a streaming chat response.
-#[derive(Deserialize, Serialize, Debug)]
+///
+/// Events are serialized with their variant in a `kind` field (`message`, `error`,
+/// `partial` or `line_error`) and its data in a `payload` field, like
+/// [`GenerateStreamEvent`](crate::types::generate::GenerateStreamEvent).
+#[derive(Deserialize, Serialize, Debug, Clone, StreamEvent)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+#[stream(no_wrapper)]
 pub enum ChatStreamEvent {
     /// A complete chat response message.
+    #[stream(message)]
     Message(ChatResponse),
     /// An error occurred during the streaming process.
+    ///
+    /// The stream parser reports errors sent by the server as [`Self::LineError`]; this
+    /// variant is kept for events built through
+    /// [`StreamEventExt::from_error`](crate::parser::StreamEventExt::from_error).
+    #[stream(error)]
     Error(String),
-    /// A partial response, returned when the content was un-parseable
+    /// A partial response with un-parseable content.
+    ///
+    /// The stream parser reports un-parseable lines as [`Self::LineError`]; this variant
+    /// is kept for events built through
+    /// [`StreamEventExt::partial`](crate::parser::StreamEventExt::partial).
+    #[stream(partial)]
     Partial {
         /// The un-parseable content.
         partial: String,
         /// An optional error message associated with the partial response.
         error: Option<String>,
     },
+    /// An error sent by the server or a line that could not be parsed, with its position
+    /// in the response body.
+    #[stream(line_error)]
+    LineError(StreamLineError),
 }
 
 /// A stream of [`ChatStreamEvent`]s for streaming chat completions.
 pub struct ChatStream {
     pub inner: Pin<Box<dyn Stream<Item = Result<ChatStreamEvent>> + Send>>,
+    pub(crate) timer: Option<StreamTimer>,
 }
 
 impl Stream for ChatStream {
@@ -362,32 +428,233 @@ impl Stream for ChatStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        self.inner.as_mut().poll_next(cx)
+        let item = futures::ready!(self.inner.as_mut().poll_next(cx));
+        if let Some(timer) = &mut self.timer {
+            timer.observe(item.as_ref());
+        }
+        std::task::Poll::Ready(item)
     }
 }
 
 impl ChatStream {
+    /// Creates a stream of already parsed events, e.g. to serve scripted events in tests.
+    ///
+    /// The stream has no [`timings`](ChatStream::timings).
+    pub fn from_events<S>(events: S) -> Self
+    where
+        S: Stream<Item = Result<ChatStreamEvent>> + Send + 'static,
+    {
+        ChatStream {
+            inner: Box::pin(events),
+            timer: None,
+        }
+    }
+
     pub fn from_bytes_stream<S>(stream: S) -> Self
     where
         S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
     {
         let parser = GenericStreamParser::<S, ChatResponse, ChatStreamEvent>::new(stream);
-        ChatStream {
-            inner: Box::pin(parser),
+        ChatStream::from_events(parser)
+    }
+
+    /// Returns the latency measurements of the stream so far.
+    ///
+    /// Returns `None` for streams that were not created by the client, e.g. with
+    /// [`ChatStream::from_bytes_stream`].
+    pub fn timings(&self) -> Option<StreamTimings> {
+        self.timer.as_ref().map(StreamTimer::timings)
+    }
+
+    /// Turns the stream into a stream of higher-level [`ChatDelta`](crate::streaming::ChatDelta)s, with thinking and
+    /// content transitions and new tool calls detected.
+    pub fn deltas(self) -> ChatDeltaStream {
+        ChatDeltaStream::new(self)
+    }
+
+    /// Ends the stream on the client side as soon as its content matches one of `conditions`.
+    ///
+    /// See [`StopStream`] for how the content is truncated.
+    pub fn stop_on(self, conditions: StopConditions) -> StopStream<Self, ChatStreamEvent> {
+        StopStream::new(self, conditions)
+    }
+
+    /// Creates a [`Broadcaster`] that fans out the stream to several subscribers.
+    ///
+    /// Add subscribers with [`Broadcaster::subscribe`] and then call [`Broadcaster::start`].
+    /// Subscribers can be turned back into a [`ChatStream`] with [`ChatStream::from`].
+    pub fn broadcast(self, config: BroadcastConfig) -> Broadcaster<ChatStreamEvent> {
+        Broadcaster::new(self, config)
+    }
+
+    /// Splits the stream into `count` independent streams that each receive every event.
+    ///
+    /// See [`ChatStream::broadcast`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn tee(self, count: usize, config: BroadcastConfig) -> Vec<ChatStream> {
+        let mut broadcaster = self.broadcast(config);
+        let streams = (0..count)
+            .map(|_| ChatStream::from(broadcaster.subscribe()))
+            .collect();
+        broadcaster.start();
+        streams
+    }
+
+    /// Turns the stream into an [`AsyncRead`](tokio::io::AsyncRead) over its content text.
+    ///
+    /// See [`TextReader`].
+    pub fn into_text_reader(self) -> TextReader {
+        TextReader::new(self.into_content())
+    }
+
+    /// Parses the content of the stream as JSON while it arrives, e.g. for requests with
+    /// a JSON `format`.
+    ///
+    /// See [`PartialJsonStream`].
+    pub fn partial_json<T: DeserializeOwned>(self) -> PartialJsonStream<T> {
+        PartialJsonStream::new(self.into_content())
+    }
+
+    /// Turns the stream into a stream of its pieces of content text, with error events
+    /// and un-parseable chunks turned into errors.
+    fn into_content(self) -> impl Stream<Item = Result<String>> + Send + 'static {
+        self.map(|event| match event? {
+            ChatStreamEvent::Message(response) => Ok(response.message.content),
+            ChatStreamEvent::Error(error) => Err(Error::Server(error)),
+            ChatStreamEvent::Partial { partial, .. } => Err(unparseable(partial)),
+            ChatStreamEvent::LineError(error) => Err(line_failure(error)),
+        })
+    }
+
+    /// Writes the events of the stream to `writer` as an NDJSON recording of
+    /// [`EventEnvelope`](crate::streaming::EventEnvelope)s and returns the number of
+    /// events written.
+    ///
+    /// The recording can be read back with [`ChatStream::replay`].
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the NDJSON is written to, e.g. a file.
+    ///
+    /// # Errors
+    ///
+    /// See [`write_ndjson`].
+    pub async fn write_ndjson<W>(self, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        write_ndjson(self, writer).await
+    }
+
+    /// Creates a stream of the events of an NDJSON recording written by
+    /// [`ChatStream::write_ndjson`], from the chunks of the recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording` - The chunks of the recording.
+    ///
+    /// # Errors
+    ///
+    /// The stream ends with an [`Error::MalformedLine`] at the first line that is not an
+    /// envelope of a [`ChatStreamEvent`], and yields an [`Error::Protocol`] for an envelope of a
+    /// newer format version.
+    pub fn replay<S>(recording: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
+    {
+        ChatStream::from_events(replay(recording))
+    }
+
+    /// Creates a stream of the events of an NDJSON recording read from `reader`, e.g. a
+    /// file.
+    ///
+    /// See [`ChatStream::replay`].
+    pub fn replay_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        Self::replay(read_recording(reader))
+    }
+
+    /// Consumes the stream and returns the complete response.
+    ///
+    /// See [`ChatStreamAccumulator`] for how the chunks are combined.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the stream, an [`Error::Server`](crate::Error::Server)
+    /// for an error event, or an [`Error::Protocol`](crate::Error::Protocol) for an
+    /// un-parseable chunk or a stream that ends before its final chunk.
+    pub async fn collect_response(self) -> Result<ChatResponse> {
+        self.accumulate(ChatStreamAccumulator::new()).await
+    }
+
+    /// Consumes the stream into `accumulator` and returns the complete response.
+    ///
+    /// Use this with [`ChatStreamAccumulator::on_delta`] to observe the chunks while they are collected.
+    ///
+    /// # Errors
+    ///
+    /// See [`ChatStream::collect_response`].
+    pub async fn accumulate(
+        mut self,
+        mut accumulator: ChatStreamAccumulator,
+    ) -> Result<ChatResponse> {
+        while let Some(event) = self.next().await {
+            accumulator.push(event?)?;
         }
+        accumulator.finish()
+    }
+}
+
+impl From<Subscriber<ChatStreamEvent>> for ChatStream {
+    fn from(subscriber: Subscriber<ChatStreamEvent>) -> Self {
+        ChatStream::from_events(subscriber)
     }
 }
 
-impl StreamEventExt<ChatResponse> for ChatStreamEvent {
-    fn from_message(msg: ChatResponse) -> Self {
-        ChatStreamEvent::Message(msg)
+impl StreamChunk for ChatStreamEvent {
+    fn content(&self) -> Option<&str> {
+        match self {
+            ChatStreamEvent::Message(response) => Some(&response.message.content),
+            _ => None,
+        }
+    }
+
+    fn content_mut(&mut self) -> Option<&mut String> {
+        match self {
+            ChatStreamEvent::Message(response) => Some(&mut response.message.content),
+            _ => None,
+        }
     }
 
-    fn from_error(err: String) -> Self {
-        ChatStreamEvent::Error(err)
+    fn thinking(&self) -> Option<&str> {
+        match self {
+            ChatStreamEvent::Message(response) => Some(&response.message.thinking),
+            _ => None,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self, ChatStreamEvent::Message(response) if response.done)
     }
 
-    fn partial(partial: String, error: Option<String>) -> Self {
-        ChatStreamEvent::Partial { partial, error }
+    fn with_content(&self, content: String) -> Option<Self> {
+        match self {
+            ChatStreamEvent::Message(response) => Some(ChatStreamEvent::Message(ChatResponse {
+                model: response.model.clone(),
+                created_at: response.created_at.clone(),
+                message: ChatResponseMessage {
+                    role: response.message.role.clone(),
+                    content,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })),
+            _ => None,
+        }
     }
 }
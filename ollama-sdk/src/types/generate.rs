@@ -2,15 +2,22 @@
 
 use std::pin::Pin;
 
-use crate::parser::{GenericStreamParser, StreamEventExt};
+use crate::parser::{GenericStreamParser, StreamEvent, StreamLineError};
+use crate::streaming::{
+    line_failure, read_recording, replay, unparseable, write_ndjson, BroadcastConfig, Broadcaster,
+    GenerateStreamAccumulator, PartialJsonStream, StopConditions, StopStream, StreamChunk,
+    StreamTimer, StreamTimings, Subscriber, TextReader,
+};
 use crate::types::Thinking;
-use crate::Result;
+use crate::{Error, Result};
 use bytes::Bytes;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use ollama_sdk_macros::FromBytes;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
 
-use super::ThinkingLevel;
+use super::{ResponseStats, ThinkingLevel};
 
 /// Represents a request to the Ollama API for text generation.
 ///
@@ -115,6 +122,22 @@ pub struct GenerateResponse {
     pub eval_duration: u64,
 }
 
+impl GenerateResponse {
+    /// Returns the timing and token statistics of the response.
+    ///
+    /// These are only reported with the final chunk of a streamed response.
+    pub fn stats(&self) -> ResponseStats {
+        ResponseStats {
+            total_duration: self.total_duration,
+            load_duration: self.load_duration,
+            prompt_eval_count: self.prompt_eval_count,
+            prompt_eval_duration: self.prompt_eval_duration,
+            eval_count: self.eval_count,
+            eval_duration: self.eval_duration,
+        }
+    }
+}
+
 /// A simplified generation request for non-streaming responses.
 ///
 /// This struct is a convenience wrapper for creating a [`GenerateRequest`]
@@ -292,25 +315,47 @@ impl From<StreamingGenerateRequest> for GenerateRequest {
 }
 
 /// Represents an event received from a streaming generation response.
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(untagged)]
+///
+/// Events are serialized with their variant in a `kind` field (`message`, `error`,
+/// `partial` or `line_error`) and its data in a `payload` field, like
+/// [`ChatStreamEvent`](crate::types::chat::ChatStreamEvent).
+#[derive(Deserialize, Serialize, Debug, Clone, StreamEvent)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+#[stream(no_wrapper)]
 pub enum GenerateStreamEvent {
     /// A chunk of the generated response.
+    #[serde(rename = "message")]
+    #[stream(message)]
     MessageChunk(GenerateResponse),
     /// An error occurred during the streaming process.
+    ///
+    /// The stream parser reports errors sent by the server as [`Self::LineError`]; this
+    /// variant is kept for events built through
+    /// [`StreamEventExt::from_error`](crate::parser::StreamEventExt::from_error).
+    #[stream(error)]
     Error(String),
-    /// A partial response, returned when the content was un-parseable
+    /// A partial response with un-parseable content.
+    ///
+    /// The stream parser reports un-parseable lines as [`Self::LineError`]; this variant
+    /// is kept for events built through
+    /// [`StreamEventExt::partial`](crate::parser::StreamEventExt::partial).
+    #[stream(partial)]
     Partial {
         /// The un-parseable content.
         partial: String,
         /// An optional error message associated with the partial response.
         error: Option<String>,
     },
+    /// An error sent by the server or a line that could not be parsed, with its position
+    /// in the response body.
+    #[stream(line_error)]
+    LineError(StreamLineError),
 }
 
 /// A stream of [`GenerateStreamEvent`]s for streaming text generation.
 pub struct GenerateStream {
     pub inner: Pin<Box<dyn Stream<Item = Result<GenerateStreamEvent>> + Send>>,
+    pub(crate) timer: Option<StreamTimer>,
 }
 
 impl Stream for GenerateStream {
@@ -320,32 +365,225 @@ impl Stream for GenerateStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        self.inner.as_mut().poll_next(cx)
+        let item = futures::ready!(self.inner.as_mut().poll_next(cx));
+        if let Some(timer) = &mut self.timer {
+            timer.observe(item.as_ref());
+        }
+        std::task::Poll::Ready(item)
     }
 }
 
 impl GenerateStream {
+    /// Creates a stream of already parsed events, e.g. to serve scripted events in tests.
+    ///
+    /// The stream has no [`timings`](GenerateStream::timings).
+    pub fn from_events<S>(events: S) -> Self
+    where
+        S: Stream<Item = Result<GenerateStreamEvent>> + Send + 'static,
+    {
+        GenerateStream {
+            inner: Box::pin(events),
+            timer: None,
+        }
+    }
+
     pub fn from_bytes_stream<S>(stream: S) -> Self
     where
         S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
     {
         let parser = GenericStreamParser::<S, GenerateResponse, GenerateStreamEvent>::new(stream);
-        GenerateStream {
-            inner: Box::pin(parser),
+        GenerateStream::from_events(parser)
+    }
+
+    /// Returns the latency measurements of the stream so far.
+    ///
+    /// Returns `None` for streams that were not created by the client, e.g. with
+    /// [`GenerateStream::from_bytes_stream`].
+    pub fn timings(&self) -> Option<StreamTimings> {
+        self.timer.as_ref().map(StreamTimer::timings)
+    }
+
+    /// Ends the stream on the client side as soon as its content matches one of `conditions`.
+    ///
+    /// See [`StopStream`] for how the content is truncated.
+    pub fn stop_on(self, conditions: StopConditions) -> StopStream<Self, GenerateStreamEvent> {
+        StopStream::new(self, conditions)
+    }
+
+    /// Creates a [`Broadcaster`] that fans out the stream to several subscribers.
+    ///
+    /// Add subscribers with [`Broadcaster::subscribe`] and then call [`Broadcaster::start`].
+    /// Subscribers can be turned back into a [`GenerateStream`] with [`GenerateStream::from`].
+    pub fn broadcast(self, config: BroadcastConfig) -> Broadcaster<GenerateStreamEvent> {
+        Broadcaster::new(self, config)
+    }
+
+    /// Splits the stream into `count` independent streams that each receive every event.
+    ///
+    /// See [`GenerateStream::broadcast`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn tee(self, count: usize, config: BroadcastConfig) -> Vec<GenerateStream> {
+        let mut broadcaster = self.broadcast(config);
+        let streams = (0..count)
+            .map(|_| GenerateStream::from(broadcaster.subscribe()))
+            .collect();
+        broadcaster.start();
+        streams
+    }
+
+    /// Turns the stream into an [`AsyncRead`](tokio::io::AsyncRead) over its content text.
+    ///
+    /// See [`TextReader`].
+    pub fn into_text_reader(self) -> TextReader {
+        TextReader::new(self.into_content())
+    }
+
+    /// Parses the content of the stream as JSON while it arrives, e.g. for requests with
+    /// a JSON `format`.
+    ///
+    /// See [`PartialJsonStream`].
+    pub fn partial_json<T: DeserializeOwned>(self) -> PartialJsonStream<T> {
+        PartialJsonStream::new(self.into_content())
+    }
+
+    /// Turns the stream into a stream of its pieces of content text, with error events
+    /// and un-parseable chunks turned into errors.
+    fn into_content(self) -> impl Stream<Item = Result<String>> + Send + 'static {
+        self.map(|event| match event? {
+            GenerateStreamEvent::MessageChunk(response) => Ok(response.response),
+            GenerateStreamEvent::Error(error) => Err(Error::Server(error)),
+            GenerateStreamEvent::Partial { partial, .. } => Err(unparseable(partial)),
+            GenerateStreamEvent::LineError(error) => Err(line_failure(error)),
+        })
+    }
+
+    /// Writes the events of the stream to `writer` as an NDJSON recording of
+    /// [`EventEnvelope`](crate::streaming::EventEnvelope)s and returns the number of
+    /// events written.
+    ///
+    /// The recording can be read back with [`GenerateStream::replay`].
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the NDJSON is written to, e.g. a file.
+    ///
+    /// # Errors
+    ///
+    /// See [`write_ndjson`].
+    pub async fn write_ndjson<W>(self, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        write_ndjson(self, writer).await
+    }
+
+    /// Creates a stream of the events of an NDJSON recording written by
+    /// [`GenerateStream::write_ndjson`], from the chunks of the recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording` - The chunks of the recording.
+    ///
+    /// # Errors
+    ///
+    /// The stream ends with an [`Error::MalformedLine`] at the first line that is not an
+    /// envelope of a [`GenerateStreamEvent`], and yields an [`Error::Protocol`] for an envelope of a
+    /// newer format version.
+    pub fn replay<S>(recording: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
+    {
+        GenerateStream::from_events(replay(recording))
+    }
+
+    /// Creates a stream of the events of an NDJSON recording read from `reader`, e.g. a
+    /// file.
+    ///
+    /// See [`GenerateStream::replay`].
+    pub fn replay_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        Self::replay(read_recording(reader))
+    }
+
+    /// Consumes the stream and returns the complete response.
+    ///
+    /// See [`GenerateStreamAccumulator`] for how the chunks are combined.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the stream, an [`Error::Server`](crate::Error::Server)
+    /// for an error event, or an [`Error::Protocol`](crate::Error::Protocol) for an
+    /// un-parseable chunk or a stream that ends before its final chunk.
+    pub async fn collect_response(self) -> Result<GenerateResponse> {
+        self.accumulate(GenerateStreamAccumulator::new()).await
+    }
+
+    /// Consumes the stream into `accumulator` and returns the complete response.
+    ///
+    /// Use this with [`GenerateStreamAccumulator::on_delta`] to observe the chunks while they are collected.
+    ///
+    /// # Errors
+    ///
+    /// See [`GenerateStream::collect_response`].
+    pub async fn accumulate(
+        mut self,
+        mut accumulator: GenerateStreamAccumulator,
+    ) -> Result<GenerateResponse> {
+        while let Some(event) = self.next().await {
+            accumulator.push(event?)?;
         }
+        accumulator.finish()
     }
 }
 
-impl StreamEventExt<GenerateResponse> for GenerateStreamEvent {
-    fn from_message(msg: GenerateResponse) -> Self {
-        GenerateStreamEvent::MessageChunk(msg)
+impl From<Subscriber<GenerateStreamEvent>> for GenerateStream {
+    fn from(subscriber: Subscriber<GenerateStreamEvent>) -> Self {
+        GenerateStream::from_events(subscriber)
     }
+}
 
-    fn from_error(err: String) -> Self {
-        GenerateStreamEvent::Error(err)
+impl StreamChunk for GenerateStreamEvent {
+    fn content(&self) -> Option<&str> {
+        match self {
+            GenerateStreamEvent::MessageChunk(response) => Some(&response.response),
+            _ => None,
+        }
+    }
+
+    fn content_mut(&mut self) -> Option<&mut String> {
+        match self {
+            GenerateStreamEvent::MessageChunk(response) => Some(&mut response.response),
+            _ => None,
+        }
     }
 
-    fn partial(partial: String, error: Option<String>) -> Self {
-        GenerateStreamEvent::Partial { partial, error }
+    fn thinking(&self) -> Option<&str> {
+        match self {
+            GenerateStreamEvent::MessageChunk(response) => Some(&response.thinking),
+            _ => None,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self, GenerateStreamEvent::MessageChunk(response) if response.done)
+    }
+
+    fn with_content(&self, content: String) -> Option<Self> {
+        match self {
+            GenerateStreamEvent::MessageChunk(response) => {
+                Some(GenerateStreamEvent::MessageChunk(GenerateResponse {
+                    model: response.model.clone(),
+                    created_at: response.created_at.clone(),
+                    response: content,
+                    ..Default::default()
+                }))
+            }
+            _ => None,
+        }
     }
 }
@@ -1,7 +1,17 @@
-use crate::Result;
+use std::fmt;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::{Error, Result};
 use bytes::Bytes;
+use futures::Stream;
+use http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use http::{HeaderMap, StatusCode};
 use serde::Serialize;
 
+/// A boxed stream of response body chunks, as produced by streaming transports.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
 /// Represents a generic HTTP request.
 ///
 /// This struct is used internally by the transport layer to construct
@@ -12,12 +22,23 @@ pub struct HttpRequest {
     pub url: String,
     /// The HTTP verb (GET, POST, PUT, DELETE) for the request.
     pub verb: HttpVerb,
-    /// The optional request body, serialized as a JSON value.
-    pub body: Option<serde_json::Value>,
+    /// The optional pre-serialized request body.
+    ///
+    /// Its media type is carried by the `Content-Type` entry of [`HttpRequest::headers`],
+    /// which [`HttpRequest::body`] and [`HttpRequest::raw_body`] set alongside the bytes.
+    pub body: Option<Bytes>,
+    /// Additional headers to send with the request.
+    pub headers: HeaderMap,
+    /// An optional timeout for this request only.
+    ///
+    /// For non-streaming requests it covers the whole exchange, including reading the body.
+    /// For streaming requests it covers the time until the response headers are received,
+    /// so that long generations are not cut off.
+    pub timeout: Option<Duration>,
 }
 
 /// Represents the HTTP verbs supported for requests.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpVerb {
     /// HTTP GET method.
     #[default]
@@ -30,13 +51,97 @@ pub enum HttpVerb {
     DELETE,
 }
 
+impl HttpVerb {
+    /// Returns the verb as an upper-case HTTP method name (e.g. `"POST"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpVerb::GET => "GET",
+            HttpVerb::POST => "POST",
+            HttpVerb::PUT => "PUT",
+            HttpVerb::DELETE => "DELETE",
+        }
+    }
+}
+
+/// Timing information collected by a transport while performing a request.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseTiming {
+    /// The time between sending the request and receiving the response headers.
+    pub time_to_headers: Duration,
+    /// The time between sending the request and receiving the complete response body.
+    ///
+    /// This is `None` for streaming responses, whose body is still being received
+    /// when the response is handed out.
+    pub total: Option<Duration>,
+}
+
 /// Represents a generic HTTP response.
 ///
-/// This struct contains the raw bytes of the response body.
-#[derive(Debug)]
+/// This struct contains the status, headers and raw bytes of the response body,
+/// along with timing information collected by the transport.
+#[derive(Default, Debug)]
 pub struct HttpResponse {
+    /// The HTTP status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
     /// The optional body of the HTTP response.
     pub body: Option<Bytes>,
+    /// Timing information for the request.
+    pub timing: ResponseTiming,
+}
+
+impl HttpResponse {
+    /// Returns the value of the `Content-Type` header, if present and valid UTF-8.
+    pub fn content_type(&self) -> Option<&str> {
+        content_type(&self.headers)
+    }
+}
+
+/// Represents a streaming HTTP response.
+///
+/// The status and headers are available as soon as the response starts,
+/// while the body is consumed incrementally through [`HttpStreamResponse::body`].
+pub struct HttpStreamResponse {
+    /// The HTTP status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The stream of response body chunks.
+    pub body: ByteStream,
+    /// Timing information for the request. Only [`ResponseTiming::time_to_headers`] is set.
+    pub timing: ResponseTiming,
+}
+
+impl HttpStreamResponse {
+    /// Creates a new [`HttpStreamResponse`] with a `200 OK` status, no headers and the given body.
+    pub fn new(body: ByteStream) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body,
+            timing: ResponseTiming::default(),
+        }
+    }
+
+    /// Returns the value of the `Content-Type` header, if present and valid UTF-8.
+    pub fn content_type(&self) -> Option<&str> {
+        content_type(&self.headers)
+    }
+}
+
+impl fmt::Debug for HttpStreamResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpStreamResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("timing", &self.timing)
+            .finish_non_exhaustive()
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok())
 }
 
 impl HttpRequest {
@@ -74,7 +179,33 @@ impl HttpRequest {
         self
     }
 
-    /// Sets the request body by serializing the given `T` into a JSON value.
+    /// Adds a header to the request, replacing any previous value with the same name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`](variant@crate::Error::Client) if the header
+    /// name or value is invalid.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::Client(format!("Invalid header name '{}': {}", name, e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| Error::Client(format!("Invalid value for header '{}': {}", name, e)))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Sets a timeout for this request only.
+    ///
+    /// See the `timeout` field of [`HttpRequest`] for what the timeout covers.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the request body by serializing the given `T` to JSON bytes.
+    ///
+    /// The body is serialized once, here, and handed to the transport as is. The
+    /// `Content-Type` header is set to `application/json`.
     ///
     /// # Arguments
     ///
@@ -85,7 +216,41 @@ impl HttpRequest {
     /// Returns an [`Error::JsonParse`](variant@crate::Error::JsonParse) if the
     /// `body` cannot be serialized to JSON.
     pub fn body<T: Serialize>(mut self, body: T) -> Result<Self> {
-        self.body = Some(serde_json::to_value(body)?);
+        let bytes = serde_json::to_vec(&body)?;
+        self.body = Some(Bytes::from(bytes));
+        self.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         Ok(self)
     }
+
+    /// Sets an already serialized request body along with its content type.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The bytes to send as the request body.
+    /// * `content_type` - The media type of `body` (e.g. `"application/json"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Client`](variant@crate::Error::Client) if the content
+    /// type is not a valid header value.
+    pub fn raw_body(self, body: impl Into<Bytes>, content_type: &str) -> Result<Self> {
+        let mut request = self.header(CONTENT_TYPE.as_str(), content_type)?;
+        request.body = Some(body.into());
+        Ok(request)
+    }
+
+    /// Returns the value of the `Content-Type` header, if present and valid UTF-8.
+    pub fn content_type(&self) -> Option<&str> {
+        content_type(&self.headers)
+    }
+
+    /// Parses the request body as JSON.
+    ///
+    /// Returns `None` if there is no body or it is not valid JSON. This is meant for
+    /// inspecting requests in tests and middleware; transports send [`HttpRequest::body`]
+    /// as is.
+    pub fn json_body(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(self.body.as_deref()?).ok()
+    }
 }
@@ -52,3 +52,22 @@ pub struct OllamaError {
     /// The error message.
     pub error: String,
 }
+
+/// Timing and token statistics reported with the final chunk of a response.
+///
+/// All durations are in nanoseconds, as reported by Ollama.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseStats {
+    /// The total duration of the request.
+    pub total_duration: u64,
+    /// The duration spent loading the model.
+    pub load_duration: u64,
+    /// The number of tokens in the prompt that were evaluated.
+    pub prompt_eval_count: u64,
+    /// The duration spent evaluating the prompt.
+    pub prompt_eval_duration: u64,
+    /// The number of tokens generated.
+    pub eval_count: u64,
+    /// The duration spent generating tokens.
+    pub eval_duration: u64,
+}
@@ -0,0 +1,194 @@
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt;
+use serde_json::json;
+
+use ollama_sdk::streaming::{ChatStreamAccumulator, GenerateStreamAccumulator};
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{ChatStream, ChatStreamEvent, StreamingChatRequest};
+use ollama_sdk::types::generate::StreamingGenerateRequest;
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn client(mock: MockTransport) -> Result<OllamaClient> {
+    OllamaClient::builder().transport(Arc::new(mock)).build()
+}
+
+fn chat_mock(lines: Vec<serde_json::Value>) -> MockTransport {
+    MockTransport::new().with_route(MockRoute::post("/api/chat").streaming(true).respond(
+        MockResponse::ndjson(lines.iter().map(|line| line.to_string())),
+    ))
+}
+
+fn tool_call(id: &str, n: u64) -> serde_json::Value {
+    json!({ "id": id, "function": { "index": 0, "name": "fibonacci", "arguments": { "n": n } } })
+}
+
+#[tokio::test]
+async fn test_collect_chat_response() -> Result<()> {
+    let mock = chat_mock(vec![
+        json!({ "model": "m", "created_at": "t0", "message": { "role": "assistant", "content": "", "thinking": "Let me " }, "done": false }),
+        json!({ "model": "m", "created_at": "t1", "message": { "role": "assistant", "content": "", "thinking": "think." }, "done": false }),
+        json!({ "model": "m", "created_at": "t2", "message": { "role": "assistant", "content": "Calling", "tool_calls": [tool_call("a", 3)] }, "done": false }),
+        json!({ "model": "m", "created_at": "t3", "message": { "role": "assistant", "content": " tools", "tool_calls": [tool_call("a", 3), tool_call("b", 5)] }, "done": false }),
+        json!({
+            "model": "m", "created_at": "t4",
+            "message": { "role": "assistant", "content": "" },
+            "done": true, "done_reason": "stop",
+            "total_duration": 100, "load_duration": 10, "prompt_eval_count": 7,
+            "prompt_eval_duration": 20, "eval_count": 4, "eval_duration": 60
+        }),
+    ]);
+
+    let response = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect_response()
+        .await?;
+
+    assert_eq!(response.model, "m");
+    assert_eq!(response.created_at, "t4");
+    assert!(matches!(response.message.role, Role::Assistant));
+    assert_eq!(response.message.thinking, "Let me think.");
+    assert_eq!(response.message.content, "Calling tools");
+    let ids: Vec<_> = response
+        .message
+        .tool_calls
+        .iter()
+        .map(|call| call.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["a", "b"]);
+    assert!(response.done);
+    assert_eq!(response.done_reason.as_deref(), Some("stop"));
+    assert_eq!(response.total_duration, 100);
+    assert_eq!(response.prompt_eval_count, 7);
+    assert_eq!(response.eval_count, 4);
+    assert_eq!(response.eval_duration, 60);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_accumulate_reports_deltas() -> Result<()> {
+    let mock = chat_mock(vec![
+        json!({ "model": "m", "message": { "role": "assistant", "content": "Hel" }, "done": false }),
+        json!({ "model": "m", "message": { "role": "assistant", "content": "lo" }, "done": true }),
+    ]);
+    let deltas = Arc::new(Mutex::new(Vec::new()));
+
+    let accumulator = ChatStreamAccumulator::new().on_delta({
+        let deltas = deltas.clone();
+        move |chunk| deltas.lock().unwrap().push(chunk.message.content.clone())
+    });
+    let response = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .accumulate(accumulator)
+        .await?;
+
+    assert_eq!(response.message.content, "Hello");
+    assert_eq!(*deltas.lock().unwrap(), vec!["Hel", "lo"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collect_fails_on_error_event_and_truncated_stream() -> Result<()> {
+    let mock = chat_mock(vec![
+        json!({ "model": "m", "message": { "role": "assistant", "content": "Hel" }, "done": false }),
+        json!({ "error": "model crashed" }),
+    ]);
+    let result = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect_response()
+        .await;
+    assert!(matches!(result, Err(Error::Server(message)) if message == "model crashed"));
+
+    let mock = chat_mock(vec![
+        json!({ "model": "m", "message": { "role": "assistant", "content": "Hel" }, "done": false }),
+    ]);
+    let result = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect_response()
+        .await;
+    assert!(matches!(result, Err(Error::Protocol(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collect_response_from_scripted_events() -> Result<()> {
+    let events = [("Hel", false), ("lo", true)].map(|(content, done)| {
+        serde_json::from_value(json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": content },
+            "done": done
+        }))
+        .map(ChatStreamEvent::Message)
+        .map_err(Error::from)
+    });
+    let stream = ChatStream::from_events(futures::stream::iter(events));
+    assert!(stream.timings().is_none());
+
+    let response = stream.collect_response().await?;
+    assert_eq!(response.message.content, "Hello");
+    assert!(response.done);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_accumulator_can_be_driven_manually() -> Result<()> {
+    let mock = chat_mock(vec![
+        json!({ "model": "m", "message": { "role": "assistant", "content": "a" }, "done": false }),
+        json!({ "model": "m", "message": { "role": "assistant", "content": "b" }, "done": true }),
+    ]);
+    let mut stream = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?;
+
+    let mut accumulator = ChatStreamAccumulator::new();
+    while let Some(event) = stream.next().await {
+        let event = event?;
+        assert!(matches!(event, ChatStreamEvent::Message(_)));
+        accumulator.push(event)?;
+        if !accumulator.is_done() {
+            assert_eq!(accumulator.response().message.content, "a");
+        }
+    }
+    assert_eq!(accumulator.finish()?.message.content, "ab");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collect_generate_response() -> Result<()> {
+    let lines = [
+        json!({ "model": "m", "created_at": "t0", "response": "Once", "thinking": "hmm", "done": false }),
+        json!({ "model": "m", "created_at": "t1", "response": " upon", "done": false }),
+        json!({ "model": "m", "created_at": "t2", "response": "", "done": true, "done_reason": "length", "eval_count": 2 }),
+    ];
+    let mock =
+        MockTransport::new().with_route(MockRoute::post("/api/generate").streaming(true).respond(
+            MockResponse::ndjson(lines.iter().map(|line| line.to_string())),
+        ));
+    let chunks = Arc::new(Mutex::new(0));
+
+    let accumulator = GenerateStreamAccumulator::new().on_delta({
+        let chunks = chunks.clone();
+        move |_| *chunks.lock().unwrap() += 1
+    });
+    let response = client(mock)?
+        .generate_stream(StreamingGenerateRequest::new(
+            "m".to_string(),
+            "Tell a story".to_string(),
+        ))
+        .await?
+        .accumulate(accumulator)
+        .await?;
+
+    assert_eq!(response.response, "Once upon");
+    assert_eq!(response.thinking, "hmm");
+    assert_eq!(response.created_at, "t2");
+    assert_eq!(response.done_reason.as_deref(), Some("length"));
+    assert_eq!(response.eval_count, 2);
+    assert_eq!(*chunks.lock().unwrap(), 3);
+    Ok(())
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "blocking")]
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use ollama_sdk::blocking::OllamaClient;
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{
+    ChatStreamEvent, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::generate::{GenerateStreamEvent, StreamingGenerateRequest};
+use ollama_sdk::types::Role;
+use ollama_sdk::Result;
+
+fn chat_line(content: &str, done: bool) -> String {
+    json!({
+        "model": "m",
+        "message": { "role": "assistant", "content": content },
+        "done": done
+    })
+    .to_string()
+}
+
+fn client(mock: &MockTransport) -> Result<OllamaClient> {
+    OllamaClient::builder()
+        .transport(Arc::new(mock.clone()))
+        .build()
+}
+
+#[test]
+fn test_blocking_chat_simple() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat").respond(MockResponse::body(chat_line("Hello!", true))),
+    );
+    let client = client(&mock)?;
+
+    let request = SimpleChatRequest::new("m".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()));
+    let response = client.chat_simple(request)?;
+    assert_eq!(response.message.content, "Hello!");
+    mock.assert_called("/api/chat");
+    Ok(())
+}
+
+#[test]
+fn test_blocking_chat_stream_is_an_iterator() -> Result<()> {
+    let mock =
+        MockTransport::new().with_route(MockRoute::post("/api/chat").streaming(true).respond(
+            MockResponse::ndjson(vec![
+                chat_line("Hel", false),
+                chat_line("lo", false),
+                chat_line("", true),
+            ]),
+        ));
+    let client = client(&mock)?;
+
+    let content: String = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))?
+        .map(|event| match event? {
+            ChatStreamEvent::Message(response) => Ok(response.message.content),
+            other => panic!("unexpected event: {:?}", other),
+        })
+        .collect::<Result<_>>()?;
+    assert_eq!(content, "Hello");
+    Ok(())
+}
+
+#[test]
+fn test_blocking_generate_stream_and_list_models() -> Result<()> {
+    let mock =
+        MockTransport::new()
+            .with_route(MockRoute::post("/api/generate").streaming(true).respond(
+                MockResponse::ndjson(vec![json!({
+                    "model": "m",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "response": "Hi",
+                    "done": true
+                })
+                .to_string()]),
+            ))
+            .with_route(
+                MockRoute::get("/api/tags").respond(MockResponse::json(json!({
+                    "models": []
+                }))),
+            );
+    let client = client(&mock)?;
+
+    let events: Vec<_> = client
+        .generate_stream(StreamingGenerateRequest::new(
+            "m".to_string(),
+            "Hi".to_string(),
+        ))?
+        .collect::<Result<_>>()?;
+    assert!(matches!(
+        events.as_slice(),
+        [GenerateStreamEvent::MessageChunk(chunk)] if chunk.response == "Hi"
+    ));
+
+    assert!(client.list_models()?.models.is_empty());
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde_json::json;
+
+use ollama_sdk::streaming::{BroadcastConfig, LagPolicy, StreamChunk};
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{ChatStream, StreamingChatRequest};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn chat_line(content: &str, done: bool) -> String {
+    json!({ "model": "m", "message": { "role": "assistant", "content": content }, "done": done })
+        .to_string()
+}
+
+fn lines(count: usize) -> Vec<String> {
+    let mut lines: Vec<_> = (0..count)
+        .map(|i| chat_line(&i.to_string(), false))
+        .collect();
+    lines.push(chat_line("", true));
+    lines
+}
+
+async fn chat_stream(mock: MockTransport) -> Result<ChatStream> {
+    OllamaClient::builder()
+        .transport(Arc::new(mock))
+        .build()?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await
+}
+
+async fn contents(stream: &mut ChatStream) -> Result<Vec<String>> {
+    let mut contents = Vec::new();
+    while let Some(event) = stream.next().await {
+        contents.push(event?.content().unwrap().to_string());
+    }
+    Ok(contents)
+}
+
+#[tokio::test]
+async fn test_tee_delivers_every_event_to_every_stream() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(lines(3))),
+    );
+    let mut streams = chat_stream(mock)
+        .await?
+        .tee(2, BroadcastConfig::new().capacity(1));
+    let second = streams.pop().unwrap();
+    let first = streams.pop().unwrap();
+
+    let (first, second) = tokio::join!(first.collect_response(), second.collect_response());
+    assert_eq!(first?.message.content, "012");
+    assert_eq!(second?.message.content, "012");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backpressure_waits_for_slow_subscriber() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(lines(10))),
+    );
+    let mut broadcaster = chat_stream(mock)
+        .await?
+        .broadcast(BroadcastConfig::new().capacity(2));
+    let mut fast = ChatStream::from(broadcaster.subscribe());
+    let mut slow = ChatStream::from(broadcaster.subscribe());
+    broadcaster.start();
+
+    let slow = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        contents(&mut slow).await
+    });
+    let expected: Vec<_> = (0..10)
+        .map(|i| i.to_string())
+        .chain([String::new()])
+        .collect();
+    assert_eq!(contents(&mut fast).await?, expected);
+    assert_eq!(slow.await.unwrap()?, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lagging_subscriber_is_dropped() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(lines(5)).chunk_delay(Duration::from_millis(5))),
+    );
+    let mut broadcaster = chat_stream(mock).await?.broadcast(
+        BroadcastConfig::new()
+            .capacity(2)
+            .lag_policy(LagPolicy::DropSubscriber),
+    );
+    let mut fast = ChatStream::from(broadcaster.subscribe());
+    let mut slow = ChatStream::from(broadcaster.subscribe());
+    broadcaster.start();
+
+    assert_eq!(
+        contents(&mut fast).await?,
+        vec!["0", "1", "2", "3", "4", ""]
+    );
+
+    assert_eq!(slow.next().await.unwrap()?.content(), Some("0"));
+    assert_eq!(slow.next().await.unwrap()?.content(), Some("1"));
+    assert!(matches!(
+        slow.next().await,
+        Some(Err(Error::SubscriberLagged { capacity: 2 }))
+    ));
+    assert!(slow.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors_are_copied_to_all_subscribers() -> Result<()> {
+    let body = stream::iter(vec![
+        Ok(Bytes::from(format!("{}\n", chat_line("a", false)))),
+        Err(Error::Transport("connection reset".into())),
+    ]);
+    let streams = ChatStream::from_bytes_stream(body).tee(3, BroadcastConfig::new());
+
+    for mut stream in streams {
+        assert_eq!(stream.next().await.unwrap()?.content(), Some("a"));
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert!(
+            matches!(&error, Error::Transport(source) if source.to_string() == "connection reset"),
+            "unexpected error: {}",
+            error
+        );
+        assert!(stream.next().await.is_none());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_source_is_dropped_without_subscribers() -> Result<()> {
+    let guard = Arc::new(());
+    let body = {
+        let guard = guard.clone();
+        stream::iter((0..100).map(|i| {
+            Ok(Bytes::from(format!(
+                "{}\n",
+                chat_line(&i.to_string(), false)
+            )))
+        }))
+        .chain(stream::pending())
+        .map(move |chunk| {
+            let _ = &guard;
+            chunk
+        })
+    };
+    let mut streams =
+        ChatStream::from_bytes_stream(Box::pin(body)).tee(1, BroadcastConfig::new().capacity(1));
+    let mut stream = streams.pop().unwrap();
+    assert_eq!(stream.next().await.unwrap()?.content(), Some("0"));
+    drop(stream);
+
+    for _ in 0..100 {
+        if Arc::strong_count(&guard) == 1 {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("source stream was not dropped");
+}
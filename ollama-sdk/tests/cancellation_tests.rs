@@ -0,0 +1,171 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{
+    ChatStreamEvent, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::generate::StreamingGenerateRequest;
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn chat_line(content: &str, done: bool) -> String {
+    json!({
+        "model": "m",
+        "message": { "role": "assistant", "content": content },
+        "done": done
+    })
+    .to_string()
+}
+
+fn generate_line(response: &str, done: bool) -> String {
+    json!({
+        "model": "m",
+        "created_at": "2024-01-01T00:00:00Z",
+        "response": response,
+        "done": done
+    })
+    .to_string()
+}
+
+fn client(mock: &MockTransport) -> Result<OllamaClient> {
+    OllamaClient::builder()
+        .transport(Arc::new(mock.clone()))
+        .build()
+}
+
+fn simple_request() -> SimpleChatRequest {
+    SimpleChatRequest::new("m".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()))
+}
+
+fn cancel_after(token: &CancellationToken, delay: Duration) {
+    let token = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        token.cancel();
+    });
+}
+
+#[tokio::test]
+async fn test_cancel_aborts_pending_simple_request() -> Result<()> {
+    let mock =
+        MockTransport::new()
+            .with_route(MockRoute::post("/api/chat").respond(
+                MockResponse::body(chat_line("late", true)).delay(Duration::from_secs(5)),
+            ));
+    let client = client(&mock)?;
+    let token = CancellationToken::new();
+    cancel_after(&token, Duration::from_millis(20));
+
+    let started = Instant::now();
+    let result = client
+        .chat_simple_with_cancel(simple_request(), token)
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Cancelled { chunks_received: 0 })
+    ));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_already_cancelled_token_does_not_send_request() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat").respond(MockResponse::body(chat_line("ok", true))),
+    );
+    let client = client(&mock)?;
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let result = client
+        .chat_simple_with_cancel(simple_request(), token)
+        .await;
+    assert!(matches!(result, Err(Error::Cancelled { .. })));
+    mock.assert_not_called("/api/chat");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_ends_chat_stream_with_chunks_received() -> Result<()> {
+    let lines = (0..20).map(|i| chat_line(&format!("t{} ", i), false));
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(lines).chunk_delay(Duration::from_millis(10))),
+    );
+    let client = client(&mock)?;
+    let token = CancellationToken::new();
+
+    let mut stream = client
+        .chat_stream_with_cancel(StreamingChatRequest::new("m".to_string()), token.clone())
+        .await?;
+    for _ in 0..3 {
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(ChatStreamEvent::Message(_)))
+        ));
+    }
+
+    token.cancel();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::Cancelled { chunks_received: 3 }))
+    ));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_wakes_stream_waiting_for_next_chunk() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/generate").streaming(true).respond(
+            MockResponse::ndjson(vec![generate_line("a", false), generate_line("", true)])
+                .chunk_delay(Duration::from_secs(5)),
+        ),
+    );
+    let client = client(&mock)?;
+    let token = CancellationToken::new();
+    cancel_after(&token, Duration::from_millis(20));
+
+    let mut stream = client
+        .generate_stream_with_cancel(
+            StreamingGenerateRequest::new("m".to_string(), "Hi".to_string()),
+            token,
+        )
+        .await?;
+    let started = Instant::now();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::Cancelled { chunks_received: 0 }))
+    ));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_uncancelled_stream_completes_normally() -> Result<()> {
+    let mock =
+        MockTransport::new().with_route(MockRoute::post("/api/chat").streaming(true).respond(
+            MockResponse::ndjson(vec![chat_line("Hello", false), chat_line("", true)]),
+        ));
+    let client = client(&mock)?;
+    let token = CancellationToken::new();
+
+    let events: Vec<_> = client
+        .chat_stream_with_cancel(StreamingChatRequest::new("m".to_string()), token.clone())
+        .await?
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.is_ok()));
+
+    token.cancel();
+    Ok(())
+}
@@ -0,0 +1,293 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use http::StatusCode;
+use serde_json::json;
+
+use ollama_sdk::transport::{
+    Cassette, MockResponse, MockRoute, MockTransport, RecordedBytes, RecordingTransport,
+    ReplayTransport,
+};
+use ollama_sdk::types::chat::{
+    ChatResponse, ChatResponseMessage, ChatStreamEvent, RegularChatRequestMessage,
+    SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::generate::StreamingGenerateRequest;
+use ollama_sdk::types::{HttpResponse, Role};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+// --- Helpers ---
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "ollama-sdk-{}-{}.cassette.json",
+        name,
+        std::process::id()
+    ))
+}
+
+fn simple_request(content: &str) -> SimpleChatRequest {
+    SimpleChatRequest::new("test-model".to_string()).add_message(RegularChatRequestMessage::new(
+        Role::User,
+        content.to_string(),
+    ))
+}
+
+fn streaming_request(content: &str) -> StreamingChatRequest {
+    StreamingChatRequest::new("test-model".to_string()).add_regular_message(
+        RegularChatRequestMessage::new(Role::User, content.to_string()),
+    )
+}
+
+async fn collect_content(client: &OllamaClient, request: StreamingChatRequest) -> Result<String> {
+    let mut stream = client.chat_stream(request).await?;
+    let mut content = String::new();
+    while let Some(event) = stream.next().await {
+        if let ChatStreamEvent::Message(response) = event? {
+            content.push_str(&response.message.content);
+        }
+    }
+    Ok(content)
+}
+
+#[tokio::test]
+async fn test_record_then_replay_chat() -> Result<()> {
+    let path = cassette_path("record-replay");
+
+    // Record against a mocked "real" server.
+    let expected_response = ChatResponse {
+        model: "test-model".to_string(),
+        message: ChatResponseMessage {
+            role: Role::Assistant,
+            content: "Recorded answer".to_string(),
+            ..Default::default()
+        },
+        done: true,
+        ..Default::default()
+    };
+    let server = MockTransport::new()
+        .with_non_streaming_http_response(HttpResponse {
+            body: Bytes::from(serde_json::to_vec(&expected_response)?).into(),
+            ..Default::default()
+        })
+        .with_raw_chat_stream_strings(vec![
+            r#"{"model":"test-model","message":{"role":"assistant","content":"Hello"},"done":false}"#.to_string(),
+            r#"{"model":"test-model","message":{"role":"assistant","content":" world"},"done":true}"#.to_string(),
+        ]);
+    let recorder = Arc::new(RecordingTransport::new(Arc::new(server), &path));
+    let client = OllamaClient::builder()
+        .transport(recorder.clone())
+        .build()?;
+
+    let recorded_simple = client.chat_simple(simple_request("Hi")).await?;
+    let recorded_stream = collect_content(&client, streaming_request("Stream me")).await?;
+    assert_eq!(recorded_simple.message.content, "Recorded answer");
+    assert_eq!(recorded_stream, "Hello world");
+
+    // Nothing is written until the recording is saved.
+    assert!(!path.exists());
+    recorder.save()?;
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette, recorder.cassette());
+    assert_eq!(cassette.interactions.len(), 2);
+    assert_eq!(cassette.interactions[1].request.path, "/api/chat");
+    assert_eq!(
+        cassette.interactions[1]
+            .response
+            .chunks
+            .as_ref()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(!cassette.interactions[1].response.truncated);
+
+    // Replay without the server, in a different order.
+    let replay = Arc::new(ReplayTransport::from_file(&path)?);
+    let client = OllamaClient::builder().transport(replay.clone()).build()?;
+
+    let replayed_stream = collect_content(&client, streaming_request("Stream me")).await?;
+    let replayed_simple = client.chat_simple(simple_request("Hi")).await?;
+    assert_eq!(replayed_stream, "Hello world");
+    assert_eq!(replayed_simple.message.content, "Recorded answer");
+    assert_eq!(replay.remaining(), 0);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_fails_on_unmatched_request() -> Result<()> {
+    let path = cassette_path("unmatched");
+    let server = MockTransport::new().with_non_streaming_http_response(HttpResponse {
+        body: Bytes::from_static(
+            br#"{"model":"test-model","message":{"role":"assistant","content":"ok"},"done":true}"#,
+        )
+        .into(),
+        ..Default::default()
+    });
+    let recorder = Arc::new(RecordingTransport::new(Arc::new(server), &path));
+    let client = OllamaClient::builder()
+        .transport(recorder.clone())
+        .build()?;
+    client.chat_simple(simple_request("Hi")).await?;
+    recorder.save()?;
+
+    let client = OllamaClient::builder()
+        .transport(Arc::new(ReplayTransport::from_file(&path)?))
+        .build()?;
+
+    // Different body: no match.
+    let error = client.chat_simple(simple_request("Bye")).await.unwrap_err();
+    assert!(
+        matches!(&error, Error::Client(message) if message.contains("POST /api/chat") && message.contains("Bye")),
+        "unexpected error: {}",
+        error
+    );
+
+    // The matching request is served once, then fails as well.
+    client.chat_simple(simple_request("Hi")).await?;
+    assert!(client.chat_simple(simple_request("Hi")).await.is_err());
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_preserves_raw_chunk_boundaries() -> Result<()> {
+    let path = cassette_path("chunks");
+    // A chunk split inside a multi-byte UTF-8 sequence must survive the round-trip.
+    let line = "{\"model\":\"m\",\"message\":{\"role\":\"assistant\",\"content\":\"caf\u{e9}\"},\"done\":true}\n";
+    let split = line.find('\u{e9}').unwrap() + 1;
+    let server = MockTransport::new().with_generate_stream_bytes(vec![
+        Bytes::copy_from_slice(&line.as_bytes()[..split]),
+        Bytes::copy_from_slice(&line.as_bytes()[split..]),
+    ]);
+    let recorder = Arc::new(RecordingTransport::new(Arc::new(server), &path));
+    let client = OllamaClient::builder()
+        .transport(recorder.clone())
+        .build()?;
+    let mut stream = client
+        .generate_stream(StreamingGenerateRequest::new(
+            "m".to_string(),
+            "p".to_string(),
+        ))
+        .await?;
+    while stream.next().await.is_some() {}
+    drop(stream);
+    recorder.save()?;
+
+    let cassette = Cassette::load(&path)?;
+    let chunks = cassette.interactions[0].response.chunks.as_ref().unwrap();
+    assert!(matches!(chunks[0].data, RecordedBytes::Binary(_)));
+    assert!(matches!(chunks[1].data, RecordedBytes::Binary(_)));
+    assert_eq!(
+        cassette.interactions[0].response.body_bytes(),
+        Bytes::from(line)
+    );
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_record_then_replay_error_responses() -> Result<()> {
+    let path = cassette_path("errors");
+    let server =
+        MockTransport::new()
+            .with_route(
+                MockRoute::post("/api/chat").streaming(false).respond(
+                    MockResponse::json(json!({ "error": "model 'x' not found" }))
+                        .status(StatusCode::NOT_FOUND),
+                ),
+            )
+            .with_route(MockRoute::post("/api/chat").streaming(true).respond(
+                MockResponse::body("upstream unavailable").status(StatusCode::BAD_GATEWAY),
+            ));
+    let recorder = Arc::new(RecordingTransport::new(Arc::new(server), &path));
+    let client = OllamaClient::builder()
+        .transport(recorder.clone())
+        .build()?;
+
+    let recorded_simple = client.chat_simple(simple_request("Hi")).await.unwrap_err();
+    let recorded_stream = client
+        .chat_stream(streaming_request("Stream me"))
+        .await
+        .err()
+        .unwrap();
+    recorder.save()?;
+
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette.interactions.len(), 2);
+    assert_eq!(cassette.interactions[0].response.status, 404);
+    assert_eq!(cassette.interactions[1].response.status, 502);
+
+    let client = OllamaClient::builder()
+        .transport(Arc::new(ReplayTransport::from_file(&path)?))
+        .build()?;
+    let replayed_simple = client.chat_simple(simple_request("Hi")).await.unwrap_err();
+    let replayed_stream = client
+        .chat_stream(streaming_request("Stream me"))
+        .await
+        .err()
+        .unwrap();
+    for (recorded, replayed) in [
+        (recorded_simple, replayed_simple),
+        (recorded_stream, replayed_stream),
+    ] {
+        assert!(matches!(recorded, Error::Http { .. }));
+        assert!(matches!(replayed, Error::Http { .. }));
+        assert_eq!(recorded.to_string(), replayed.to_string());
+    }
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_dropped_while_recording_is_marked_truncated() -> Result<()> {
+    let server = MockTransport::new().with_raw_chat_stream_strings(vec![
+        r#"{"model":"test-model","message":{"role":"assistant","content":"Hello"},"done":false}"#
+            .to_string(),
+        r#"{"model":"test-model","message":{"role":"assistant","content":" world"},"done":true}"#
+            .to_string(),
+    ]);
+    let recorder = Arc::new(RecordingTransport::new(
+        Arc::new(server),
+        cassette_path("truncated"),
+    ));
+    let client = OllamaClient::builder()
+        .transport(recorder.clone())
+        .build()?;
+
+    let mut stream = client.chat_stream(streaming_request("Stream me")).await?;
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(ChatStreamEvent::Message(_)))
+    ));
+    drop(stream);
+
+    let cassette = recorder.cassette();
+    assert!(cassette.interactions[0].response.truncated);
+
+    let client = OllamaClient::builder()
+        .transport(Arc::new(ReplayTransport::new(cassette)))
+        .build()?;
+    let mut stream = client.chat_stream(streaming_request("Stream me")).await?;
+    let mut content = String::new();
+    let error = loop {
+        match stream.next().await {
+            Some(Ok(ChatStreamEvent::Message(response))) => {
+                content.push_str(&response.message.content)
+            }
+            Some(Ok(event)) => panic!("unexpected event {:?}", event),
+            Some(Err(error)) => break error,
+            None => panic!("a truncated stream must not end cleanly"),
+        }
+    };
+    assert!(content.starts_with("Hello"));
+    assert!(matches!(error, Error::Client(message) if message.contains("dropped")));
+    Ok(())
+}
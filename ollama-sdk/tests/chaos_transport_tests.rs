@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde_json::json;
+
+use ollama_sdk::parser::StreamLineErrorKind;
+use ollama_sdk::transport::{ChaosFault, ChaosTransport, MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{
+    ChatStreamEvent, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn chat_line(content: &str, done: bool) -> String {
+    format!(
+        "{}\n",
+        json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": content },
+            "done": done
+        })
+    )
+}
+
+fn streaming_mock(lines: Vec<String>) -> MockTransport {
+    MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::chunks(lines)),
+    )
+}
+
+fn client(chaos: ChaosTransport) -> Result<OllamaClient> {
+    OllamaClient::builder().transport(Arc::new(chaos)).build()
+}
+
+fn stream_request() -> StreamingChatRequest {
+    StreamingChatRequest::new("m".to_string())
+        .add_regular_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()))
+}
+
+async fn collect_events(client: &OllamaClient) -> Result<Vec<ChatStreamEvent>> {
+    let mut stream = client.chat_stream(stream_request()).await?;
+    let mut events = Vec::new();
+    while let Some(event) = stream.inner.next().await {
+        events.push(event?);
+    }
+    Ok(events)
+}
+
+fn contents(events: &[ChatStreamEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            ChatStreamEvent::Message(response) => Some(response.message.content.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_random_splits_do_not_change_parsed_events() -> Result<()> {
+    let lines = vec![
+        chat_line("héllo ", false),
+        chat_line("wörld 🌍", false),
+        chat_line("", true),
+    ];
+
+    for seed in 0..20 {
+        let chaos = ChaosTransport::new(Arc::new(streaming_mock(lines.clone())))
+            .seed(seed)
+            .split_rate(1.0);
+        let events = collect_events(&client(chaos)?).await?;
+        assert_eq!(contents(&events), vec!["héllo ", "wörld 🌍", ""]);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scheduled_split_inside_utf8_sequence() -> Result<()> {
+    let line = chat_line("🌍", true);
+    let offset = line.find('🌍').unwrap() + 2;
+    let mock = streaming_mock(vec![line]);
+    let chaos =
+        ChaosTransport::new(Arc::new(mock.clone())).on_chunk(0, 0, ChaosFault::SplitAt(offset));
+
+    let events = collect_events(&client(chaos)?).await?;
+    assert_eq!(contents(&events), vec!["🌍"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_truncated_stream_ends_with_partial_line() -> Result<()> {
+    let mock = streaming_mock(vec![chat_line("one", false), chat_line("two", true)]);
+    let chaos = ChaosTransport::new(Arc::new(mock)).on_chunk(0, 1, ChaosFault::TruncateAt(10));
+
+    let events = collect_events(&client(chaos)?).await?;
+    assert_eq!(contents(&events), vec!["one"]);
+    let first_line = chat_line("one", false).len() as u64;
+    assert!(matches!(
+        events.last(),
+        Some(ChatStreamEvent::LineError(error))
+            if error.kind() == StreamLineErrorKind::Truncated
+                && error.line() == 1
+                && error.offset() == first_line
+                && error.raw().len() == 10
+                && !error.can_continue()
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_corrupted_line_is_reported_and_stream_continues() -> Result<()> {
+    let mock = streaming_mock(vec![
+        chat_line("one", false),
+        chat_line("two", false),
+        chat_line("three", true),
+    ]);
+    let chaos = ChaosTransport::new(Arc::new(mock)).on_chunk(0, 1, ChaosFault::CorruptAt(0));
+
+    let events = collect_events(&client(chaos)?).await?;
+    assert_eq!(contents(&events), vec!["one", "three"]);
+    assert!(matches!(
+        &events[1],
+        ChatStreamEvent::LineError(error)
+            if matches!(error.kind(), StreamLineErrorKind::Malformed(_))
+                && error.line() == 1
+                && error.can_continue()
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_stream_error_ends_stream() -> Result<()> {
+    let mock = streaming_mock(vec![chat_line("one", false), chat_line("two", true)]);
+    let chaos = ChaosTransport::new(Arc::new(mock)).stream_error_rate(1.0);
+    let client = client(chaos)?;
+
+    let mut stream = client.chat_stream(stream_request()).await?;
+    assert!(matches!(
+        stream.inner.next().await,
+        Some(Err(Error::Transport(_)))
+    ));
+    assert!(stream.inner.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scheduled_request_failure_is_not_forwarded() -> Result<()> {
+    let mock = MockTransport::new().with_route(MockRoute::post("/api/chat").respond(
+        MockResponse::json(json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": "ok" },
+            "done": true
+        })),
+    ));
+    let chaos = ChaosTransport::new(Arc::new(mock.clone())).on_request(0, ChaosFault::Fail);
+    let client = client(chaos)?;
+    let request = SimpleChatRequest::new("m".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()));
+
+    let result = client.chat_simple(request.clone()).await;
+    assert!(matches!(result, Err(Error::Transport(_))));
+    assert_eq!(mock.call_count("/api/chat"), 0);
+
+    let response = client.chat_simple(request).await?;
+    assert_eq!(response.message.content, "ok");
+    assert_eq!(mock.call_count("/api/chat"), 1);
+    Ok(())
+}
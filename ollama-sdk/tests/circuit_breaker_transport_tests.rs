@@ -0,0 +1,190 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::StatusCode;
+use serde_json::json;
+
+use ollama_sdk::transport::{
+    CircuitBreakerTransport, CircuitState, MockResponse, MockRoute, MockTransport,
+};
+use ollama_sdk::types::chat::{RegularChatRequestMessage, SimpleChatRequest};
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn ok() -> MockResponse {
+    MockResponse::json(json!({
+        "model": "m",
+        "message": { "role": "assistant", "content": "ok" },
+        "done": true
+    }))
+}
+
+fn server_error() -> MockResponse {
+    MockResponse::json(json!({ "error": "out of memory" }))
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn not_found() -> MockResponse {
+    MockResponse::json(json!({ "error": "model 'm' not found" })).status(StatusCode::NOT_FOUND)
+}
+
+fn request() -> SimpleChatRequest {
+    SimpleChatRequest::new("m".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()))
+}
+
+fn setup(
+    responses: Vec<MockResponse>,
+    breaker: impl FnOnce(CircuitBreakerTransport) -> CircuitBreakerTransport,
+) -> Result<(MockTransport, Arc<CircuitBreakerTransport>, OllamaClient)> {
+    let route = responses
+        .into_iter()
+        .fold(MockRoute::post("/api/chat"), MockRoute::respond);
+    let mock = MockTransport::new().with_route(route);
+    let breaker = Arc::new(breaker(CircuitBreakerTransport::new(Arc::new(
+        mock.clone(),
+    ))));
+    let client = OllamaClient::builder().transport(breaker.clone()).build()?;
+    Ok((mock, breaker, client))
+}
+
+#[tokio::test]
+async fn test_opens_after_failure_rate_and_fails_fast() -> Result<()> {
+    let (mock, breaker, client) = setup(
+        vec![ok(), server_error(), server_error(), ok()],
+        |breaker| {
+            breaker
+                .minimum_requests(3)
+                .failure_rate_threshold(0.6)
+                .open_duration(Duration::from_secs(60))
+        },
+    )?;
+
+    client.chat_simple(request()).await?;
+    assert!(client.chat_simple(request()).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert!(client.chat_simple(request()).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    match client.chat_simple(request()).await {
+        Err(Error::CircuitOpen { retry_after }) => {
+            assert!(retry_after > Duration::from_secs(50));
+        }
+        other => panic!("expected CircuitOpen, got {:?}", other.map(|_| ())),
+    }
+    assert_eq!(mock.call_count("/api/chat"), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_errors_do_not_open_the_circuit() -> Result<()> {
+    let (_, breaker, client) = setup(vec![], |breaker| {
+        breaker.minimum_requests(1).failure_rate_threshold(0.1)
+    })?;
+
+    // No route is left, so the mock answers with a client error.
+    for _ in 0..3 {
+        assert!(matches!(
+            client.chat_simple(request()).await,
+            Err(Error::Client(_))
+        ));
+    }
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_error_statuses_do_not_open_the_circuit() -> Result<()> {
+    let (_, breaker, client) = setup(vec![not_found(), not_found(), not_found()], |breaker| {
+        breaker.minimum_requests(1).failure_rate_threshold(0.1)
+    })?;
+
+    for _ in 0..3 {
+        assert!(matches!(
+            client.chat_simple(request()).await,
+            Err(Error::Http { status, .. }) if status == StatusCode::NOT_FOUND
+        ));
+    }
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "failure rate threshold")]
+fn test_rejects_zero_failure_rate_threshold() {
+    let mock = MockTransport::new();
+    let _ = CircuitBreakerTransport::new(Arc::new(mock)).failure_rate_threshold(0.0);
+}
+
+#[tokio::test]
+async fn test_successful_probe_closes_the_circuit() -> Result<()> {
+    let (mock, breaker, client) = setup(vec![server_error(), ok(), ok()], |breaker| {
+        breaker
+            .minimum_requests(1)
+            .open_duration(Duration::from_millis(30))
+    })?;
+
+    assert!(client.chat_simple(request()).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(matches!(
+        client.chat_simple(request()).await,
+        Err(Error::CircuitOpen { .. })
+    ));
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    client.chat_simple(request()).await?;
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    client.chat_simple(request()).await?;
+    assert_eq!(mock.call_count("/api/chat"), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_probe_reopens_the_circuit() -> Result<()> {
+    let (mock, breaker, client) = setup(vec![server_error(), server_error()], |breaker| {
+        breaker
+            .minimum_requests(1)
+            .open_duration(Duration::from_millis(30))
+    })?;
+
+    assert!(client.chat_simple(request()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(matches!(
+        client.chat_simple(request()).await,
+        Err(Error::Http { status, .. }) if status.is_server_error()
+    ));
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(matches!(
+        client.chat_simple(request()).await,
+        Err(Error::CircuitOpen { .. })
+    ));
+    assert_eq!(mock.call_count("/api/chat"), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_half_open_limits_concurrent_probes() -> Result<()> {
+    let (_, breaker, client) = setup(
+        vec![server_error(), ok().delay(Duration::from_millis(50))],
+        |breaker| {
+            breaker
+                .minimum_requests(1)
+                .open_duration(Duration::from_millis(10))
+        },
+    )?;
+
+    assert!(client.chat_simple(request()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let probe = client.chat_simple(request());
+    let concurrent = async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        client.chat_simple(request()).await
+    };
+    let (probe, concurrent) = tokio::join!(probe, concurrent);
+
+    probe?;
+    assert!(matches!(concurrent, Err(Error::CircuitOpen { .. })));
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    Ok(())
+}
@@ -26,6 +26,7 @@ async fn test_chat_simple() -> Result<()> {
     let mock_transport = Arc::new(MockTransport::new().with_non_streaming_http_response(
         HttpResponse {
             body: Bytes::from(http_response_body).into(),
+            ..Default::default()
         },
     ));
 
@@ -81,6 +82,9 @@ async fn test_chat_stream() -> Result<()> {
                     .as_str(),
                 );
             }
+            ChatStreamEvent::LineError(error) => {
+                received_content.push_str(format!("\nLine Error: {}", error).as_str());
+            }
         }
     }
 
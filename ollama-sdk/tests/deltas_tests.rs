@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde_json::json;
+
+use ollama_sdk::streaming::ChatDelta;
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::StreamingChatRequest;
+use ollama_sdk::{OllamaClient, Result};
+
+async fn deltas(lines: Vec<serde_json::Value>) -> Result<Vec<ChatDelta>> {
+    let mock =
+        MockTransport::new().with_route(MockRoute::post("/api/chat").streaming(true).respond(
+            MockResponse::ndjson(lines.iter().map(|line| line.to_string())),
+        ));
+    let client = OllamaClient::builder().transport(Arc::new(mock)).build()?;
+    client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .deltas()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect()
+}
+
+fn chunk(thinking: &str, content: &str) -> serde_json::Value {
+    json!({
+        "model": "m",
+        "message": { "role": "assistant", "content": content, "thinking": thinking },
+        "done": false
+    })
+}
+
+/// Renders deltas compactly so whole sequences can be compared.
+fn describe(deltas: &[ChatDelta]) -> Vec<String> {
+    deltas
+        .iter()
+        .map(|delta| match delta {
+            ChatDelta::ThinkingStarted => "thinking-started".to_string(),
+            ChatDelta::ThinkingDelta(text) => format!("thinking:{}", text),
+            ChatDelta::ThinkingFinished => "thinking-finished".to_string(),
+            ChatDelta::ContentDelta(text) => format!("content:{}", text),
+            ChatDelta::ToolCallStarted(call) => format!("tool:{}", call.id),
+            ChatDelta::Done { done_reason, stats } => format!(
+                "done:{}:{}",
+                done_reason.as_deref().unwrap_or("-"),
+                stats.eval_count
+            ),
+            ChatDelta::Error(error) => format!("error:{}", error),
+            ChatDelta::Partial { partial, .. } => format!("partial:{}", partial),
+            ChatDelta::LineError(error) => {
+                format!("line-error:{}:{}", error.line(), error.message())
+            }
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_thinking_then_content_transitions() -> Result<()> {
+    let deltas = deltas(vec![
+        chunk("Hmm", ""),
+        chunk(", sky", ""),
+        chunk("", "Rayleigh"),
+        chunk("", ""),
+        chunk("", " scattering"),
+        json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": "" },
+            "done": true, "done_reason": "stop", "eval_count": 5
+        }),
+    ])
+    .await?;
+
+    assert_eq!(
+        describe(&deltas),
+        vec![
+            "thinking-started",
+            "thinking:Hmm",
+            "thinking:, sky",
+            "thinking-finished",
+            "content:Rayleigh",
+            "content: scattering",
+            "done:stop:5",
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tool_calls_are_started_once_and_end_thinking() -> Result<()> {
+    let call = |id: &str| json!({ "id": id, "function": { "name": "lookup", "arguments": {} } });
+    let deltas = deltas(vec![
+        chunk("Need data", ""),
+        json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": "", "tool_calls": [call("a")] },
+            "done": false
+        }),
+        json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": "", "tool_calls": [call("a"), call("b")] },
+            "done": true
+        }),
+    ])
+    .await?;
+
+    assert_eq!(
+        describe(&deltas),
+        vec![
+            "thinking-started",
+            "thinking:Need data",
+            "thinking-finished",
+            "tool:a",
+            "tool:b",
+            "done:-:0",
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_thinking_in_final_chunk_is_finished_before_done() -> Result<()> {
+    let deltas = deltas(vec![
+        chunk("", "Hi"),
+        json!({ "error": "overloaded" }),
+        json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": "", "thinking": "late thought" },
+            "done": true
+        }),
+    ])
+    .await?;
+
+    assert_eq!(
+        describe(&deltas),
+        vec![
+            "content:Hi",
+            "line-error:1:overloaded",
+            "thinking-started",
+            "thinking:late thought",
+            "thinking-finished",
+            "done:-:0",
+        ]
+    );
+    Ok(())
+}
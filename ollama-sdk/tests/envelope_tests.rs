@@ -0,0 +1,161 @@
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde_json::error::Category;
+use serde_json::{json, Value};
+
+use ollama_sdk::parser::StreamLineErrorKind;
+use ollama_sdk::streaming::EventEnvelope;
+use ollama_sdk::types::chat::{ChatStream, ChatStreamEvent};
+use ollama_sdk::types::generate::{GenerateStream, GenerateStreamEvent};
+use ollama_sdk::{Error, Result};
+
+fn line(value: Value) -> Result<Bytes> {
+    Ok(Bytes::from(format!("{}\n", value)))
+}
+
+fn chat_body() -> Vec<Result<Bytes>> {
+    vec![
+        line(
+            json!({ "model": "m", "message": { "role": "assistant", "content": "Hi" }, "done": false }),
+        ),
+        line(json!({ "error": "slow down" })),
+        Ok(Bytes::from("{\"model\": \n")),
+        line(
+            json!({ "model": "m", "message": { "role": "assistant", "content": "!" }, "done": true }),
+        ),
+    ]
+}
+
+fn generate_body() -> Vec<Result<Bytes>> {
+    vec![
+        line(json!({ "model": "m", "created_at": "t", "response": "Once", "done": false })),
+        line(json!({ "error": "model crashed" })),
+    ]
+}
+
+#[test]
+fn test_chat_and_generate_events_share_kinds() -> Result<()> {
+    let chat = serde_json::to_value(ChatStreamEvent::Error("boom".to_string()))?;
+    let generate = serde_json::to_value(GenerateStreamEvent::Error("boom".to_string()))?;
+    assert_eq!(chat, json!({ "kind": "error", "payload": "boom" }));
+    assert_eq!(chat, generate);
+
+    let partial = GenerateStreamEvent::Partial {
+        partial: "{".to_string(),
+        error: None,
+    };
+    assert_eq!(
+        serde_json::to_value(partial)?,
+        json!({ "kind": "partial", "payload": { "partial": "{", "error": null } })
+    );
+
+    let envelope: EventEnvelope<GenerateStreamEvent> = serde_json::from_value(json!({
+        "version": 1,
+        "received_at": 1_700_000_000_123u64,
+        "kind": "message",
+        "payload": { "model": "m", "created_at": "t", "response": "x", "done": true }
+    }))?;
+    assert_eq!(envelope.received_at, 1_700_000_000_123);
+    assert!(matches!(
+        envelope.event,
+        GenerateStreamEvent::MessageChunk(response) if response.response == "x"
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_recording_replays_every_event() -> Result<()> {
+    let mut recording = Vec::new();
+    let written = ChatStream::from_bytes_stream(stream::iter(chat_body()))
+        .write_ndjson(&mut recording)
+        .await?;
+    assert_eq!(written, 4);
+
+    // Split the recording into small chunks, as a file or socket would deliver it.
+    let chunks: Vec<_> = recording
+        .chunks(7)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect();
+    let events = ChatStream::replay(stream::iter(chunks))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(events.len(), 4);
+    assert!(
+        matches!(&events[0], ChatStreamEvent::Message(response) if response.message.content == "Hi")
+    );
+    let first_line = line(
+        json!({ "model": "m", "message": { "role": "assistant", "content": "Hi" }, "done": false }),
+    )?
+    .len() as u64;
+    assert!(matches!(
+        &events[1],
+        ChatStreamEvent::LineError(error)
+            if error.is_server_error()
+                && error.message() == "slow down"
+                && error.line() == 1
+                && error.offset() == first_line
+    ));
+    assert!(matches!(
+        &events[2],
+        ChatStreamEvent::LineError(error)
+            if error.kind() == StreamLineErrorKind::Malformed(Category::Eof)
+                && error.line() == 2
+                && error.raw().starts_with(b"{\"model\":")
+                && error.can_continue()
+    ));
+    assert!(matches!(&events[3], ChatStreamEvent::Message(response) if response.done));
+
+    let response = ChatStream::replay_reader(std::io::Cursor::new(recording))
+        .collect_response()
+        .await;
+    assert!(matches!(response, Err(Error::Server(error)) if error == "slow down"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_recording_replays_into_stream() -> Result<()> {
+    let mut recording = Vec::new();
+    GenerateStream::from_bytes_stream(stream::iter(generate_body()))
+        .write_ndjson(&mut recording)
+        .await?;
+
+    let mut stream = GenerateStream::replay_reader(std::io::Cursor::new(recording));
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(GenerateStreamEvent::MessageChunk(response))) if response.response == "Once"
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(GenerateStreamEvent::LineError(error)))
+            if error.is_server_error() && error.message() == "model crashed" && error.line() == 1
+    ));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_rejects_foreign_lines_and_versions() {
+    let body = vec![
+        line(json!({ "version": 1, "received_at": 0, "kind": "error", "payload": "a" })),
+        line(json!({ "version": 99, "received_at": 0, "kind": "error", "payload": "b" })),
+        line(json!({ "model": "m", "created_at": "t", "response": "raw", "done": true })),
+        line(json!({ "version": 1, "received_at": 0, "kind": "error", "payload": "c" })),
+    ];
+    let mut stream = GenerateStream::replay(stream::iter(body));
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(GenerateStreamEvent::Error(error))) if error == "a"
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::Protocol(message))) if message.contains("version 99")
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::MalformedLine(error))) if error.line() == 2
+    ));
+    assert!(stream.next().await.is_none());
+}
@@ -1,8 +1,12 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use bytes::Bytes;
 use futures::{stream, Stream, StreamExt};
-use ollama_sdk::parser::{GenericStreamParser, StreamEventExt};
-use ollama_sdk::types::chat::{ChatResponse, ChatStreamEvent};
-use ollama_sdk::Result;
+use ollama_sdk::parser::{Framing, GenericStreamParser, ParserConfig, StreamEventExt, StreamLimit};
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{ChatResponse, ChatStreamEvent, StreamingChatRequest};
+use ollama_sdk::{Error, OllamaClient, Result};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -201,6 +205,39 @@ async fn test_generic_parser_message_split_across_chunks() {
     assert!(parser.next().await.is_none());
 }
 
+#[tokio::test]
+async fn test_generic_parser_byte_sized_chunks_and_crlf() {
+    let raw = "{\"id\": \"1\", \"content\": \"a\"}\r\n\r\n{\"error\": \"boom\"}\r\n";
+    let chunks: Vec<String> = raw.chars().map(|c| c.to_string()).collect();
+    let stream = mock_byte_stream(chunks.iter().map(String::as_str).collect());
+    let mut parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::new(stream);
+
+    assert_eq!(
+        parser.next().await.unwrap().unwrap(),
+        MockStreamEvent::Message(MockMessage {
+            id: "1".to_string(),
+            content: "a".to_string(),
+        })
+    );
+    assert_eq!(
+        parser.next().await.unwrap().unwrap(),
+        MockStreamEvent::Error("boom".to_string())
+    );
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_generic_parser_partial_reports_message_error() {
+    let stream = mock_byte_stream(vec!["{\"id\": \"1\"}\n"]);
+    let mut parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::new(stream);
+
+    let event = parser.next().await.unwrap().unwrap();
+    assert!(matches!(
+        event,
+        MockStreamEvent::Partial { error: Some(error), .. } if error.contains("missing field `content`")
+    ));
+}
+
 #[tokio::test]
 async fn test_generic_parser_stream_ends_with_partial() {
     let raw_partial = r#"{"id": "1", "content": "incomplete"#;
@@ -281,3 +318,202 @@ async fn test_chat_parser_tool_call_invocation() {
 
     assert!(parser.next().await.is_none());
 }
+
+// --- Parser limits ---
+
+#[tokio::test]
+async fn test_generic_parser_line_limit_on_unterminated_line() {
+    let stream = mock_byte_stream(vec!["{\"id\": \"1\", ", "\"content\": ", "\"endless..."]);
+    let mut parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::with_config(
+        stream,
+        ParserConfig::new().max_line_bytes(16),
+    );
+
+    assert!(matches!(
+        parser.next().await,
+        Some(Err(Error::StreamLimitExceeded {
+            limit: StreamLimit::LineBytes,
+            max: 16
+        }))
+    ));
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_generic_parser_line_limit_allows_lines_up_to_max() {
+    let line = r#"{"id":"1","content":"ok"}"#;
+    let long = r#"{"id":"2","content":"too long"}"#;
+    let stream = mock_byte_stream(vec![&format!("{}\n{}\n", line, long)]);
+    let mut parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::with_config(
+        stream,
+        ParserConfig::new().max_line_bytes(line.len()),
+    );
+
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(MockStreamEvent::Message(_)))
+    ));
+    assert!(matches!(
+        parser.next().await,
+        Some(Err(Error::StreamLimitExceeded {
+            limit: StreamLimit::LineBytes,
+            ..
+        }))
+    ));
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_generic_parser_buffered_bytes_limit() {
+    let stream = mock_byte_stream(vec!["{\"id\":", " \"1\"", &" ".repeat(64)]);
+    let mut parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::with_config(
+        stream,
+        ParserConfig::new().max_buffered_bytes(32),
+    );
+
+    let error = parser.next().await.unwrap().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Stream limit exceeded: more than 32 buffered bytes"
+    );
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_generic_parser_max_events() {
+    let line = r#"{"id":"1","content":"a"}"#;
+    let stream = mock_byte_stream(vec![&format!("{0}\n{0}\n{0}\n", line)]);
+    let parser = GenericStreamParser::<_, MockMessage, MockStreamEvent>::with_config(
+        stream,
+        ParserConfig::new().max_events(2),
+    );
+
+    let events: Vec<_> = parser.collect().await;
+    assert_eq!(events.len(), 3);
+    assert!(events[..2].iter().all(|event| event.is_ok()));
+    assert!(matches!(
+        events[2],
+        Err(Error::StreamLimitExceeded {
+            limit: StreamLimit::Events,
+            max: 2
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_client_parser_config_applies_to_streams() -> Result<()> {
+    let line = r#"{"model":"m","message":{"role":"assistant","content":"hi"},"done":false}"#;
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(vec![line; 5])),
+    );
+    let client = OllamaClient::builder()
+        .transport(Arc::new(mock))
+        .parser_config(ParserConfig::new().max_events(3))
+        .build()?;
+
+    let events: Vec<_> = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect()
+        .await;
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[3], Err(Error::StreamLimitExceeded { .. })));
+    Ok(())
+}
+
+// --- Server-sent events ---
+
+fn sse_parser(
+    chunks: Vec<&str>,
+) -> GenericStreamParser<impl Stream<Item = Result<Bytes>>, MockMessage, MockStreamEvent> {
+    GenericStreamParser::with_config(
+        Box::pin(mock_byte_stream(chunks)),
+        ParserConfig::new().framing(Framing::Sse),
+    )
+}
+
+#[test]
+fn test_framing_from_content_type() {
+    assert_eq!(
+        Framing::from_content_type(Some("text/event-stream; charset=utf-8")),
+        Framing::Sse
+    );
+    assert_eq!(
+        Framing::from_content_type(Some("Text/Event-Stream")),
+        Framing::Sse
+    );
+    assert_eq!(
+        Framing::from_content_type(Some("application/x-ndjson")),
+        Framing::Ndjson
+    );
+    assert_eq!(Framing::from_content_type(None), Framing::Ndjson);
+}
+
+#[tokio::test]
+async fn test_sse_parser_fields_comments_and_done() {
+    let mut parser = sse_parser(vec![
+        ": keep-alive\n\n",
+        "id: 7\nretry: 1500\nevent: message\n",
+        "data: {\"id\": \"1\",\r\n",
+        "data:\"content\": \"hi\"}\r\n\r\n",
+        "event: error\ndata: {\"error\": \"overloaded\"}\n\n",
+        "data: [DONE]\n\n",
+        "data: {\"id\": \"ignored\", \"content\": \"\"}\n\n",
+    ]);
+
+    assert_eq!(
+        parser.next().await.unwrap().unwrap(),
+        MockStreamEvent::Message(MockMessage {
+            id: "1".to_string(),
+            content: "hi".to_string(),
+        })
+    );
+    assert_eq!(parser.last_event_id(), Some("7"));
+    assert_eq!(parser.retry(), Some(Duration::from_millis(1500)));
+    assert_eq!(
+        parser.next().await.unwrap().unwrap(),
+        MockStreamEvent::Error("overloaded".to_string())
+    );
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_sse_parser_dispatches_last_event_without_blank_line() {
+    let mut parser = sse_parser(vec!["data: {\"id\": \"1\", ", "\"content\": \"end\"}"]);
+
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(MockStreamEvent::Message(message))) if message.content == "end"
+    ));
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_client_selects_sse_framing_from_content_type() -> Result<()> {
+    let line = r#"{"model":"m","message":{"role":"assistant","content":"hi"},"done":true}"#;
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat").streaming(true).respond(
+            MockResponse::chunks(vec![
+                format!("data: {}\n\n", line),
+                "data: [DONE]\n\n".to_string(),
+            ])
+            .header("content-type", "text/event-stream"),
+        ),
+    );
+    let client = OllamaClient::builder().transport(Arc::new(mock)).build()?;
+
+    let events: Vec<_> = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_>>()?;
+    assert!(matches!(
+        events.as_slice(),
+        [ChatStreamEvent::Message(response)] if response.message.content == "hi"
+    ));
+    Ok(())
+}
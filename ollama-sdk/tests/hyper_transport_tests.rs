@@ -0,0 +1,183 @@
+#![cfg(feature = "hyper")]
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use ollama_sdk::transport::HyperTransport;
+use ollama_sdk::types::chat::{
+    ChatStreamEvent, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+// --- Helpers for serving canned HTTP responses over TCP ---
+
+/// Reads a single request (head and body) from `socket`, returning `None` once the peer closes.
+async fn read_request(socket: &mut TcpStream) -> Option<String> {
+    let mut received = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        if n == 0 {
+            return None;
+        }
+        received.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&received).to_string();
+        if let Some(head_end) = text.find("\r\n\r\n") {
+            let content_length = text[..head_end]
+                .lines()
+                .find_map(|l| {
+                    l.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if received.len() >= head_end + 4 + content_length {
+                return Some(text);
+            }
+        }
+    }
+}
+
+/// Accepts connections and answers requests with `responses` in order, keeping each
+/// connection open. Returns the raw requests and the number of connections accepted.
+async fn serve(responses: Vec<String>) -> (String, tokio::task::JoinHandle<(Vec<String>, usize)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut responses = responses.into_iter();
+        let mut requests = Vec::new();
+        let mut connections = 0;
+        'accept: loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            connections += 1;
+            while let Some(request) = read_request(&mut socket).await {
+                requests.push(request);
+                match responses.next() {
+                    Some(response) => socket.write_all(response.as_bytes()).await.unwrap(),
+                    None => break 'accept,
+                }
+                if responses.len() == 0 {
+                    break 'accept;
+                }
+            }
+        }
+        (requests, connections)
+    });
+    (base_url, server)
+}
+
+fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn chunked_response(lines: &[&str]) -> String {
+    let mut response = String::from(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ntransfer-encoding: chunked\r\n\r\n",
+    );
+    for line in lines {
+        let chunk = format!("{}\n", line);
+        response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+    }
+    response.push_str("0\r\n\r\n");
+    response
+}
+
+fn client(base_url: &str, api_key: Option<String>) -> Result<OllamaClient> {
+    OllamaClient::builder()
+        .transport(Arc::new(HyperTransport::new(base_url, api_key)?))
+        .build()
+}
+
+#[tokio::test]
+async fn test_hyper_chat_simple_and_stream_reuse_connection() -> Result<()> {
+    let (base_url, server) = serve(vec![
+        json_response(
+            "200 OK",
+            r#"{"model":"m","message":{"role":"assistant","content":"Hello"},"done":true}"#,
+        ),
+        chunked_response(&[
+            r#"{"model":"m","message":{"role":"assistant","content":"Hel"},"done":false}"#,
+            r#"{"model":"m","message":{"role":"assistant","content":"lo"},"done":true}"#,
+        ]),
+    ])
+    .await;
+    let client = client(&base_url, Some("secret".to_string()))?;
+
+    let request = SimpleChatRequest::new("m".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()));
+    let response = client.chat_simple(request).await?;
+    assert_eq!(response.message.content, "Hello");
+
+    let mut stream = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?;
+    let mut content = String::new();
+    while let Some(event) = stream.next().await {
+        if let ChatStreamEvent::Message(response) = event? {
+            content.push_str(&response.message.content);
+        }
+    }
+    assert_eq!(content, "Hello");
+
+    let (requests, connections) = server.await.unwrap();
+    assert_eq!(connections, 1);
+    assert!(requests[0].starts_with("POST /api/chat HTTP/1.1"));
+    assert!(requests[0].contains("authorization: Bearer secret"));
+    assert!(requests[0].contains(r#""model":"m""#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hyper_error_status() -> Result<()> {
+    let (base_url, server) = serve(vec![json_response(
+        "404 Not Found",
+        r#"{"error":"model 'missing' not found"}"#,
+    )])
+    .await;
+    let client = client(&base_url, None)?;
+
+    let error = client.list_models().await.unwrap_err();
+    assert!(
+        matches!(
+            &error,
+            Error::Http { status, message }
+                if *status == 404 && message == "model 'missing' not found"
+        ),
+        "unexpected error: {}",
+        error
+    );
+    server.await.unwrap();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hyper_keeps_base_url_path() -> Result<()> {
+    let (base_url, server) = serve(vec![json_response("200 OK", r#"{"models":[]}"#)]).await;
+    let client = client(&format!("{}/ollama/", base_url), None)?;
+
+    client.list_models().await?;
+    let (requests, _) = server.await.unwrap();
+    assert!(requests[0].starts_with("GET /ollama/api/tags HTTP/1.1"));
+    Ok(())
+}
+
+#[test]
+fn test_hyper_rejects_unsupported_urls() {
+    assert!(matches!(
+        HyperTransport::new("https://example.com", None),
+        Err(Error::Client(_))
+    ));
+    assert!(matches!(
+        HyperTransport::new("not a url", None),
+        Err(Error::Client(_))
+    ));
+}
@@ -0,0 +1,105 @@
+use bytes::Bytes;
+use futures::stream;
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+use ollama_sdk::streaming::{EventEnvelope, ENVELOPE_VERSION};
+use ollama_sdk::types::chat::{ChatStream, ChatStreamEvent};
+use ollama_sdk::types::generate::GenerateStream;
+use ollama_sdk::{Error, Result};
+
+fn line(value: serde_json::Value) -> Result<Bytes> {
+    Ok(Bytes::from(format!("{}\n", value)))
+}
+
+fn chat_body() -> Vec<Result<Bytes>> {
+    vec![
+        line(
+            json!({ "model": "m", "message": { "role": "assistant", "content": "", "thinking": "hmm" }, "done": false }),
+        ),
+        line(
+            json!({ "model": "m", "message": { "role": "assistant", "content": "Hello,\n" }, "done": false }),
+        ),
+        line(
+            json!({ "model": "m", "message": { "role": "assistant", "content": "wörld" }, "done": true }),
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn test_chat_text_reader_reads_content_only() -> Result<()> {
+    let mut reader = ChatStream::from_bytes_stream(stream::iter(chat_body())).into_text_reader();
+    let mut text = String::new();
+    reader.read_to_string(&mut text).await?;
+    assert_eq!(text, "Hello,\nwörld");
+
+    let reader = ChatStream::from_bytes_stream(stream::iter(chat_body())).into_text_reader();
+    let mut lines = reader.lines();
+    assert_eq!(lines.next_line().await?.as_deref(), Some("Hello,"));
+    assert_eq!(lines.next_line().await?.as_deref(), Some("wörld"));
+    assert_eq!(lines.next_line().await?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_text_reader_maps_errors() -> Result<()> {
+    let body = stream::iter(vec![
+        line(json!({ "model": "m", "created_at": "t", "response": "Once", "done": false })),
+        line(json!({ "error": "model crashed" })),
+    ]);
+    let mut reader = GenerateStream::from_bytes_stream(body).into_text_reader();
+
+    let mut buf = [0u8; 16];
+    let n = reader.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Once");
+
+    let error = reader.read(&mut buf).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Other);
+    let source = error.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert!(matches!(*source, Error::Server(message) if message == "model crashed"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_ndjson_archives_events() -> Result<()> {
+    let mut body = chat_body();
+    body.insert(2, line(json!({ "error": "slow down" })));
+    let mut archive = Vec::new();
+    let written = ChatStream::from_bytes_stream(stream::iter(body))
+        .write_ndjson(&mut archive)
+        .await?;
+    assert_eq!(written, 4);
+
+    let archive = String::from_utf8(archive).unwrap();
+    let events = archive
+        .lines()
+        .map(serde_json::from_str::<EventEnvelope<ChatStreamEvent>>)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    assert_eq!(events.len(), 4);
+    assert!(events
+        .iter()
+        .all(|envelope| envelope.version == ENVELOPE_VERSION && envelope.received_at > 0));
+    let events: Vec<_> = events.into_iter().map(|envelope| envelope.event).collect();
+    assert!(
+        matches!(&events[1], ChatStreamEvent::Message(response) if response.message.content == "Hello,\n")
+    );
+    assert!(matches!(
+        &events[2],
+        ChatStreamEvent::LineError(error) if error.is_server_error() && error.message() == "slow down" && error.line() == 2
+    ));
+    assert!(matches!(&events[3], ChatStreamEvent::Message(response) if response.done));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_ndjson_stops_at_stream_error() {
+    let mut body = chat_body();
+    body.insert(2, Err(Error::Transport("connection reset".into())));
+    let mut archive = Vec::new();
+    let result = ChatStream::from_bytes_stream(stream::iter(body))
+        .write_ndjson(&mut archive)
+        .await;
+
+    assert!(matches!(result, Err(Error::Transport(_))));
+    assert_eq!(archive.iter().filter(|&&b| b == b'\n').count(), 2);
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde_json::json;
+
+use ollama_sdk::limiter::Limits;
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{
+    ChatStream, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::Role;
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn chat_line(content: &str) -> String {
+    json!({
+        "model": "m",
+        "message": { "role": "assistant", "content": content },
+        "done": true
+    })
+    .to_string()
+}
+
+fn mock() -> MockTransport {
+    let simple = (0..10).fold(MockRoute::post("/api/chat").streaming(false), |route, _| {
+        route.respond(MockResponse::body(chat_line("ok")))
+    });
+    let streaming = (0..10).fold(MockRoute::post("/api/chat").streaming(true), |route, _| {
+        route.respond(MockResponse::ndjson(vec![chat_line("streamed")]))
+    });
+    MockTransport::new()
+        .with_route(simple)
+        .with_route(streaming)
+}
+
+fn client(mock: &MockTransport, limits: Limits) -> Result<OllamaClient> {
+    OllamaClient::builder()
+        .transport(Arc::new(mock.clone()))
+        .limits(limits)
+        .build()
+}
+
+fn simple(model: &str, content: &str) -> SimpleChatRequest {
+    SimpleChatRequest::new(model.to_string()).add_message(RegularChatRequestMessage::new(
+        Role::User,
+        content.to_string(),
+    ))
+}
+
+async fn open_stream(client: &OllamaClient, model: &str) -> Result<ChatStream> {
+    client
+        .chat_stream(StreamingChatRequest::new(model.to_string()))
+        .await
+}
+
+#[tokio::test]
+async fn test_open_stream_holds_global_permit() -> Result<()> {
+    let mock = mock();
+    let client = client(
+        &mock,
+        Limits::new()
+            .max_in_flight(1)
+            .max_queue_wait(Duration::from_millis(30)),
+    )?;
+
+    let stream = open_stream(&client, "m").await?;
+    match client.chat_simple(simple("other", "queued")).await {
+        Err(Error::QueueTimeout { waited }) => assert!(waited >= Duration::from_millis(30)),
+        other => panic!("expected QueueTimeout, got {:?}", other.map(|_| ())),
+    }
+
+    drop(stream);
+    client.chat_simple(simple("other", "after")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permit_is_released_when_stream_ends() -> Result<()> {
+    let mock = mock();
+    let client = client(
+        &mock,
+        Limits::new()
+            .max_in_flight(1)
+            .max_queue_wait(Duration::from_millis(30)),
+    )?;
+
+    let mut stream = open_stream(&client, "m").await?;
+    while let Some(event) = stream.next().await {
+        event?;
+    }
+    client.chat_simple(simple("m", "after")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_per_model_cap_does_not_block_other_models() -> Result<()> {
+    let mock = mock();
+    let client = client(
+        &mock,
+        Limits::new()
+            .max_in_flight_per_model(2)
+            .model_max_in_flight("big", 1)
+            .max_queue_wait(Duration::from_millis(30)),
+    )?;
+
+    let _big = open_stream(&client, "big").await?;
+    assert!(matches!(
+        client.chat_simple(simple("big", "queued")).await,
+        Err(Error::QueueTimeout { .. })
+    ));
+
+    let _small = open_stream(&client, "small").await?;
+    client.chat_simple(simple("small", "second")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queued_requests_are_served_by_priority() -> Result<()> {
+    let mock = mock();
+    let client = client(&mock, Limits::new().max_in_flight(1))?;
+
+    let stream = open_stream(&client, "m").await?;
+    let low = {
+        let client = client.with_priority(-1);
+        tokio::spawn(async move { client.chat_simple(simple("m", "low")).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let normal = {
+        let client = client.clone();
+        tokio::spawn(async move { client.chat_simple(simple("m", "normal")).await })
+    };
+    let high = {
+        let client = client.with_priority(10);
+        tokio::spawn(async move { client.chat_simple(simple("m", "high")).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(mock.call_count("/api/chat"), 1);
+
+    drop(stream);
+    for handle in [low, normal, high] {
+        handle.await.unwrap()?;
+    }
+
+    let order: Vec<_> = mock
+        .requests_to("/api/chat")
+        .iter()
+        .skip(1)
+        .map(|request| request.body["messages"][0]["content"].clone())
+        .collect();
+    assert_eq!(order, vec!["high", "normal", "low"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancelled_queued_request_does_not_leak_permit() -> Result<()> {
+    let mock = mock();
+    let client = client(&mock, Limits::new().max_in_flight(1))?;
+
+    let stream = open_stream(&client, "m").await?;
+    let cancelled = tokio::time::timeout(
+        Duration::from_millis(20),
+        client.chat_simple(simple("m", "x")),
+    )
+    .await;
+    assert!(cancelled.is_err());
+
+    drop(stream);
+    tokio::time::timeout(Duration::from_secs(1), client.chat_simple(simple("m", "y")))
+        .await
+        .expect("permit was leaked")?;
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde_json::json;
+
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{
+    ChatResponse, ChatResponseMessage, ChatStreamEvent, RegularChatRequestMessage,
+    SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::{HttpRequest, Role};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn client(mock: &MockTransport) -> Result<OllamaClient> {
+    OllamaClient::builder()
+        .transport(Arc::new(mock.clone()))
+        .build()
+}
+
+fn simple_request(model: &str, content: &str) -> SimpleChatRequest {
+    SimpleChatRequest::new(model.to_string()).add_message(RegularChatRequestMessage::new(
+        Role::User,
+        content.to_string(),
+    ))
+}
+
+fn chat_body(model: &str, content: &str) -> serde_json::Value {
+    json!({
+        "model": model,
+        "message": { "role": "assistant", "content": content },
+        "done": true
+    })
+}
+
+#[tokio::test]
+async fn test_routes_match_on_body_and_capture_requests() -> Result<()> {
+    let mock = MockTransport::new()
+        .with_route(
+            MockRoute::post("/api/chat")
+                .when_body(|body| body["model"] == "model-a")
+                .respond(MockResponse::json(chat_body("model-a", "from a"))),
+        )
+        .with_route(
+            MockRoute::post("/api/chat")
+                .when_body(|body| body["model"] == "model-b")
+                .respond(MockResponse::json(chat_body("model-b", "from b"))),
+        );
+    let client = client(&mock)?;
+
+    let response = client
+        .chat_simple(simple_request("model-b", "Hi B"))
+        .await?;
+    assert_eq!(response.message.content, "from b");
+    let response = client
+        .chat_simple(simple_request("model-a", "Hi A"))
+        .await?;
+    assert_eq!(response.message.content, "from a");
+
+    assert_eq!(mock.call_count("/api/chat"), 2);
+    mock.assert_called_with("/api/chat", |body| {
+        body["model"] == "model-a" && body["messages"][0]["content"] == "Hi A"
+    });
+    mock.assert_not_called("/api/generate");
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].body["model"], "model-b");
+    assert_eq!(requests[0].body["stream"], false);
+    assert!(!requests[0].streaming);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_body_is_sent_as_json_bytes() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat").respond(MockResponse::json(chat_body("m", "a cat"))),
+    );
+    let client = client(&mock)?;
+
+    let request = SimpleChatRequest::new("m".to_string()).add_message(
+        RegularChatRequestMessage::new(Role::User, "What is this?".to_string()),
+    );
+    client.chat_simple(request).await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].headers["content-type"], "application/json");
+    assert_eq!(
+        requests[0].body["messages"][0]["content"],
+        json!("What is this?")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_http_request_bodies() -> Result<()> {
+    let request = HttpRequest::new("/api/chat")
+        .post()
+        .body(json!({ "model": "m" }))?;
+    assert_eq!(request.body.as_deref(), Some(&br#"{"model":"m"}"#[..]));
+    assert_eq!(request.content_type(), Some("application/json"));
+    assert_eq!(request.json_body(), Some(json!({ "model": "m" })));
+
+    let request = HttpRequest::new("/upload")
+        .put()
+        .raw_body("plain text", "text/plain")?;
+    assert_eq!(request.content_type(), Some("text/plain"));
+    assert_eq!(request.json_body(), None);
+
+    assert!(HttpRequest::new("/upload")
+        .raw_body("x", "bad\nvalue")
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_route_queue_is_served_in_order_then_exhausted() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .respond(MockResponse::json(chat_body("m", "first")))
+            .respond(MockResponse::json(chat_body("m", "second"))),
+    );
+    let client = client(&mock)?;
+
+    let first = client.chat_simple(simple_request("m", "1")).await?;
+    let second = client.chat_simple(simple_request("m", "2")).await?;
+    assert_eq!(first.message.content, "first");
+    assert_eq!(second.message.content, "second");
+
+    let error = client
+        .chat_simple(simple_request("m", "3"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Client(message) if message.contains("POST /api/chat")));
+    assert_eq!(mock.call_count("/api/chat"), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_errors_and_delays() -> Result<()> {
+    let mock = MockTransport::new()
+        .with_route(
+            MockRoute::post("/api/chat")
+                .streaming(false)
+                .respond(MockResponse::error(|| Error::Server("model is loading".into())))
+                .respond(
+                    MockResponse::json(chat_body("m", "slow")).delay(Duration::from_millis(30)),
+                ),
+        )
+        .with_route(
+            MockRoute::post("/api/chat").streaming(true).respond(
+                MockResponse::ndjson([
+                    r#"{"model":"m","message":{"role":"assistant","content":"partial"},"done":false}"#,
+                ])
+                .chunk_delay(Duration::from_millis(5))
+                .then_error(|| Error::Protocol("connection reset".into())),
+            ),
+        );
+    let client = client(&mock)?;
+
+    let error = client
+        .chat_simple(simple_request("m", "1"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Server(message) if message == "model is loading"));
+
+    let started = Instant::now();
+    let response = client.chat_simple(simple_request("m", "2")).await?;
+    assert_eq!(response.message.content, "slow");
+    assert!(started.elapsed() >= Duration::from_millis(30));
+
+    let request = StreamingChatRequest::new("m".to_string())
+        .add_regular_message(RegularChatRequestMessage::new(Role::User, "3".to_string()));
+    let mut stream = client.chat_stream(request).await?;
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(ChatStreamEvent::Message(_)))
+    ));
+    assert!(matches!(stream.next().await, Some(Err(Error::Protocol(_)))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_stream_events_are_sent_as_server_lines() -> Result<()> {
+    let mock = MockTransport::new().with_chat_stream_events(vec![
+        ChatStreamEvent::Message(ChatResponse {
+            model: "m".to_string(),
+            message: ChatResponseMessage {
+                role: Role::Assistant,
+                content: "Hello".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        ChatStreamEvent::Error("out of memory".to_string()),
+    ]);
+    let client = client(&mock)?;
+
+    let request = StreamingChatRequest::new("m".to_string());
+    let events = client.chat_stream(request).await?.collect::<Vec<_>>().await;
+    assert!(matches!(&events[0], Ok(ChatStreamEvent::Message(r)) if r.message.content == "Hello"));
+    assert!(matches!(
+        &events[1],
+        Ok(ChatStreamEvent::LineError(e)) if e.is_server_error() && e.message() == "out of memory"
+    ));
+    assert!(mock.requests()[0].streaming);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_error_status_fails_like_http_transports() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .respond(
+                MockResponse::json(json!({ "error": "model 'missing' not found" }))
+                    .status(http::StatusCode::NOT_FOUND),
+            )
+            .respond(MockResponse::body("overloaded").status(http::StatusCode::BAD_GATEWAY)),
+    );
+    let client = client(&mock)?;
+
+    let error = client
+        .chat_simple(simple_request("missing", "Hi"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(http::StatusCode::NOT_FOUND));
+    assert!(matches!(error, Error::Http { message, .. } if message == "model 'missing' not found"));
+
+    let error = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(error, Error::Http { status, message } if status == 502 && message == "overloaded")
+    );
+    Ok(())
+}
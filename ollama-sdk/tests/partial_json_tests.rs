@@ -0,0 +1,249 @@
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use ollama_sdk::streaming::{PartialJson, PartialJsonParser};
+use ollama_sdk::types::chat::ChatStream;
+use ollama_sdk::types::generate::GenerateStream;
+use ollama_sdk::{Error, Result};
+
+const DOCUMENT: &str = r#"{"name": "Ada \"the\" Countess\\", "tags": ["math", "poétry\n"],
+    "emoji": "😀!", "age": 36, "ratio": -1.5e2, "ok": true, "none": null,
+    "nested": {"empty": {}, "list": [[], [1, 2]]}}"#;
+
+fn snapshot(text: &str) -> Option<Value> {
+    let mut parser = PartialJsonParser::new();
+    parser.push(text);
+    parser.snapshot()
+}
+
+/// Returns `true` if `partial` could grow into `complete`.
+fn is_prefix_of(partial: &Value, complete: &Value) -> bool {
+    match (partial, complete) {
+        (Value::String(partial), Value::String(complete)) => complete.starts_with(partial.as_str()),
+        (Value::Array(partial), Value::Array(complete)) => {
+            partial.len() <= complete.len()
+                && partial.iter().zip(complete).enumerate().all(|(i, (p, c))| {
+                    if i + 1 == partial.len() {
+                        is_prefix_of(p, c)
+                    } else {
+                        p == c
+                    }
+                })
+        }
+        (Value::Object(partial), Value::Object(complete)) => partial
+            .iter()
+            .all(|(key, p)| complete.get(key).is_some_and(|c| is_prefix_of(p, c))),
+        (partial, complete) => partial == complete,
+    }
+}
+
+#[test]
+fn test_every_split_point_gives_a_consistent_snapshot() {
+    let complete: Value = serde_json::from_str(DOCUMENT).unwrap();
+    for (split, _) in DOCUMENT.char_indices().skip(1) {
+        let mut parser = PartialJsonParser::new();
+        parser.push(&DOCUMENT[..split]);
+        let partial = parser
+            .snapshot()
+            .unwrap_or_else(|| panic!("no snapshot for {:?}", &DOCUMENT[..split]));
+        assert!(
+            is_prefix_of(&partial, &complete),
+            "snapshot {} of {:?} is not a prefix",
+            partial,
+            &DOCUMENT[..split]
+        );
+
+        parser.push(&DOCUMENT[split..]);
+        assert!(parser.is_complete());
+        assert_eq!(parser.snapshot().as_ref(), Some(&complete));
+    }
+}
+
+#[test]
+fn test_char_by_char_snapshots_only_grow() {
+    let mut parser = PartialJsonParser::new();
+    let mut snapshots = Vec::new();
+    for (i, c) in DOCUMENT.char_indices() {
+        parser.push(&DOCUMENT[i..i + c.len_utf8()]);
+        if let Some(snapshot) = parser.snapshot() {
+            if snapshots.last() != Some(&snapshot) {
+                snapshots.push(snapshot);
+            }
+        }
+    }
+    let complete: Value = serde_json::from_str(DOCUMENT).unwrap();
+    assert_eq!(snapshots.last(), Some(&complete));
+    for pair in snapshots.windows(2) {
+        assert!(
+            is_prefix_of(&pair[0], &pair[1]),
+            "{} -> {}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn test_splits_inside_strings_and_escapes() {
+    assert_eq!(snapshot(r#"{"a": "x"#), Some(json!({ "a": "x" })));
+    assert_eq!(snapshot(r#"{"a": "x\"#), Some(json!({ "a": "x" })));
+    assert_eq!(snapshot(r#"{"a": "x\""#), Some(json!({ "a": "x\"" })));
+    assert_eq!(snapshot(r#"{"a": "x\u00"#), Some(json!({ "a": "x" })));
+    assert_eq!(snapshot(r#"{"a": "xé"#), Some(json!({ "a": "xé" })));
+    assert_eq!(snapshot(r#"{"a": "\ud83d"#), Some(json!({ "a": "" })));
+    assert_eq!(snapshot(r#"{"a": "\ud83d\ude"#), Some(json!({ "a": "" })));
+    assert_eq!(snapshot(r#"{"a": "😀"#), Some(json!({ "a": "😀" })));
+    assert_eq!(snapshot(r#"["a\\"#), Some(json!(["a\\"])));
+    assert_eq!(snapshot(r#""root"#), Some(json!("root")));
+}
+
+#[test]
+fn test_incomplete_members_are_left_out() {
+    assert_eq!(snapshot(""), None);
+    assert_eq!(snapshot("  "), None);
+    assert_eq!(snapshot("{"), Some(json!({})));
+    assert_eq!(snapshot(r#"{"ke"#), Some(json!({})));
+    assert_eq!(snapshot(r#"{"a": 1, "b"#), Some(json!({ "a": 1 })));
+    assert_eq!(snapshot(r#"{"a": 1, "b":"#), Some(json!({ "a": 1 })));
+    assert_eq!(snapshot(r#"{"a": 1,"#), Some(json!({ "a": 1 })));
+    assert_eq!(snapshot(r#"{"a": tr"#), Some(json!({})));
+    assert_eq!(snapshot(r#"{"a": -"#), Some(json!({})));
+    assert_eq!(snapshot(r#"{"a": 1.5e"#), Some(json!({})));
+    assert_eq!(snapshot(r#"{"a": 12"#), Some(json!({})));
+    assert_eq!(snapshot(r#"{"a": 12 "#), Some(json!({ "a": 12 })));
+    assert_eq!(
+        snapshot(r#"{"a": true, "b": nul"#),
+        Some(json!({ "a": true }))
+    );
+    assert_eq!(snapshot("[1, 2,"), Some(json!([1, 2])));
+    assert_eq!(snapshot("[1, 2"), Some(json!([1])));
+    assert_eq!(snapshot("[1, [2, {"), Some(json!([1, [2, {}]])));
+    assert_eq!(snapshot("-"), None);
+    assert_eq!(snapshot("nul"), None);
+}
+
+#[test]
+fn test_invalid_documents() {
+    let mut parser = PartialJsonParser::new();
+    parser.push(r#"{"a" 1}"#);
+    assert!(parser.is_invalid());
+    assert_eq!(parser.snapshot(), None);
+
+    let mut parser = PartialJsonParser::new();
+    parser.push(r#"{"a": "\q"}"#);
+    assert!(parser.is_invalid());
+
+    let mut parser = PartialJsonParser::new();
+    parser.push("[1] [2]");
+    assert!(parser.is_invalid());
+    assert!(matches!(parser.parse::<Value>(), Err(Error::JsonParse(_))));
+}
+
+#[test]
+fn test_large_document_in_small_pieces() {
+    let records: Vec<Value> = (0..500)
+        .map(|i| json!({ "id": i, "name": format!("record \"{}\" é😀", i), "even": i % 2 == 0 }))
+        .collect();
+    let complete = json!({ "records": records, "text": "ab\\cd".repeat(10_000) });
+    let document = serde_json::to_string_pretty(&complete).unwrap();
+
+    let mut parser = PartialJsonParser::new();
+    let mut records_seen = 0;
+    let mut text_seen = 0;
+    let mut rest = document.as_str();
+    while !rest.is_empty() {
+        let mut split = rest.len().min(48);
+        while !rest.is_char_boundary(split) {
+            split += 1;
+        }
+        parser.push(&rest[..split]);
+        rest = &rest[split..];
+
+        let snapshot = parser.snapshot().unwrap();
+        let records = snapshot["records"].as_array().map_or(0, Vec::len);
+        let text = snapshot["text"].as_str().map_or(0, str::len);
+        assert!(records >= records_seen && text >= text_seen);
+        (records_seen, text_seen) = (records, text);
+    }
+    assert!(parser.is_complete());
+    assert_eq!(parser.snapshot(), Some(complete.clone()));
+    assert_eq!(parser.parse::<Value>().unwrap(), complete);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Person {
+    name: String,
+    tags: Vec<String>,
+}
+
+fn chat_line(content: &str, done: bool) -> Result<Bytes> {
+    let line = json!({ "model": "m", "message": { "role": "assistant", "content": content }, "done": done });
+    Ok(Bytes::from(format!("{}\n", line)))
+}
+
+#[tokio::test]
+async fn test_chat_partial_json_stream() -> Result<()> {
+    let body = stream::iter(vec![
+        chat_line(r#"{"na"#, false),
+        chat_line(r#"me": "A"#, false),
+        chat_line(r#"da \"#, false),
+        chat_line(r#""L\"", "#, false),
+        chat_line(r#""tags": ["x"]"#, false),
+        chat_line("}", false),
+        chat_line("", true),
+    ]);
+    let items: Vec<_> = ChatStream::from_bytes_stream(body)
+        .partial_json::<Person>()
+        .collect()
+        .await;
+    let items = items.into_iter().collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(
+        items,
+        vec![
+            PartialJson::Snapshot(json!({})),
+            PartialJson::Snapshot(json!({ "name": "A" })),
+            PartialJson::Snapshot(json!({ "name": "Ada " })),
+            PartialJson::Snapshot(json!({ "name": "Ada \"L\"" })),
+            PartialJson::Snapshot(json!({ "name": "Ada \"L\"", "tags": ["x"] })),
+            PartialJson::Complete(Person {
+                name: "Ada \"L\"".to_string(),
+                tags: vec!["x".to_string()],
+            }),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_partial_json_stream_errors() {
+    let line = |response: &str, done: bool| {
+        let line = json!({ "model": "m", "created_at": "t", "response": response, "done": done });
+        Ok(Bytes::from(format!("{}\n", line)))
+    };
+    let body = stream::iter(vec![line(r#"{"name": "Ada", "#, false), line("", true)]);
+    let mut stream = GenerateStream::from_bytes_stream(body).partial_json::<Person>();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(PartialJson::Snapshot(value))) if value == json!({ "name": "Ada" })
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::JsonParse(_)))
+    ));
+    assert!(stream.next().await.is_none());
+
+    let body = stream::iter(vec![
+        line("[1,", false),
+        Ok(Bytes::from("{\"error\":\"boom\"}\n")),
+    ]);
+    let mut stream = GenerateStream::from_bytes_stream(body).partial_json::<Vec<u32>>();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(PartialJson::Snapshot(value))) if value == json!([1])
+    ));
+    assert!(matches!(stream.next().await, Some(Err(Error::Server(message))) if message == "boom"));
+    assert!(stream.next().await.is_none());
+}
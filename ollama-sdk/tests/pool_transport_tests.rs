@@ -0,0 +1,290 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use http::StatusCode;
+use serde_json::json;
+
+use ollama_sdk::transport::{
+    MockResponse, MockRoute, MockTransport, PoolStrategy, PoolTransport, Transport,
+};
+use ollama_sdk::types::chat::{RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest};
+use ollama_sdk::types::{HttpRequest, Role};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+fn chat_route(content: &str, responses: usize) -> MockRoute {
+    (0..responses).fold(MockRoute::post("/api/chat"), |route, _| {
+        route.respond(MockResponse::json(json!({
+            "model": "m",
+            "message": { "role": "assistant", "content": content },
+            "done": true
+        })))
+    })
+}
+
+fn host(content: &str) -> MockTransport {
+    MockTransport::new().with_route(chat_route(content, 10))
+}
+
+fn client(pool: PoolTransport) -> Result<OllamaClient> {
+    OllamaClient::builder().transport(Arc::new(pool)).build()
+}
+
+fn request(model: &str) -> SimpleChatRequest {
+    SimpleChatRequest::new(model.to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()))
+}
+
+async fn answer(client: &OllamaClient, model: &str) -> Result<String> {
+    Ok(client.chat_simple(request(model)).await?.message.content)
+}
+
+#[tokio::test]
+async fn test_round_robin_cycles_through_hosts() -> Result<()> {
+    let pool = PoolTransport::new(PoolStrategy::RoundRobin)
+        .with_host("a", Arc::new(host("a")))
+        .with_host("b", Arc::new(host("b")));
+    let client = client(pool)?;
+
+    let mut answers = Vec::new();
+    for _ in 0..4 {
+        answers.push(answer(&client, "m").await?);
+    }
+    assert_eq!(answers, vec!["a", "b", "a", "b"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_least_in_flight_avoids_host_with_open_stream() -> Result<()> {
+    let a = MockTransport::new()
+        .with_route(
+            MockRoute::post("/api/chat")
+                .streaming(true)
+                .respond(MockResponse::ndjson(vec![json!({
+                    "model": "m",
+                    "message": { "role": "assistant", "content": "streamed" },
+                    "done": true
+                })
+                .to_string()])),
+        )
+        .with_route(chat_route("a", 10));
+    let pool = PoolTransport::new(PoolStrategy::LeastInFlight)
+        .with_host("a", Arc::new(a))
+        .with_host("b", Arc::new(host("b")));
+    let pool = Arc::new(pool);
+    let client = OllamaClient::builder().transport(pool.clone()).build()?;
+
+    let stream = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?;
+    assert_eq!(pool.status()[0].in_flight, 1);
+    assert_eq!(answer(&client, "m").await?, "b");
+    assert_eq!(answer(&client, "m").await?, "b");
+
+    drop(stream);
+    assert_eq!(pool.status()[0].in_flight, 0);
+    assert_eq!(answer(&client, "m").await?, "a");
+    Ok(())
+}
+
+fn running_models(model: &str) -> MockResponse {
+    MockResponse::json(json!({
+            "models": [{
+                "model": model,
+                "size": 0,
+                "digest": "",
+                "details": {
+                    "parent_model": "",
+                    "format": "gguf",
+                    "family": "llama",
+                    "families": ["llama"],
+                    "parameter_size": "7B",
+                    "quantization_level": "Q4_0"
+                },
+                "expires_at": "",
+                "size_vram": 0,
+                "context_length": 4096
+            }]
+    }))
+}
+
+fn ps(model: &str) -> MockRoute {
+    MockRoute::get("/api/ps").respond(running_models(model))
+}
+
+#[tokio::test]
+async fn test_model_affinity_prefers_host_with_loaded_model() -> Result<()> {
+    let a = host("a").with_route(ps("llama3"));
+    let b = host("b").with_route(ps("qwen3"));
+    let pool = PoolTransport::new(PoolStrategy::ModelAffinity)
+        .with_host("a", Arc::new(a.clone()))
+        .with_host("b", Arc::new(b.clone()));
+    let client = client(pool)?;
+
+    assert_eq!(answer(&client, "qwen3").await?, "b");
+    assert_eq!(answer(&client, "llama3").await?, "a");
+    assert_eq!(answer(&client, "qwen3").await?, "b");
+
+    // Running models are cached, so `/api/ps` is only queried once per host.
+    assert_eq!(a.call_count("/api/ps"), 1);
+    assert_eq!(b.call_count("/api/ps"), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_model_affinity_does_not_wait_for_hanging_host() -> Result<()> {
+    let hanging =
+        MockRoute::get("/api/ps").respond(running_models("llama3").delay(Duration::from_secs(60)));
+    let a = host("a").with_route(hanging);
+    let b = host("b").with_route(ps("qwen3"));
+    let pool = Arc::new(
+        PoolTransport::new(PoolStrategy::ModelAffinity)
+            .with_host("a", Arc::new(a))
+            .with_host("b", Arc::new(b))
+            .failure_threshold(1)
+            .affinity_timeout(Duration::from_millis(50)),
+    );
+    let client = OllamaClient::builder().transport(pool.clone()).build()?;
+
+    // The timed out `/api/ps` request ejects `a`, so even a model it may have loaded
+    // is served by `b`.
+    let answer = tokio::time::timeout(Duration::from_secs(5), answer(&client, "llama3"))
+        .await
+        .expect("the pool waits at most the affinity timeout")?;
+    assert_eq!(answer, "b");
+    assert_eq!(pool.status()[0].consecutive_failures, 1);
+    assert!(pool.status()[0].ejected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_model_affinity_refreshes_running_models_once_at_a_time() -> Result<()> {
+    let slow_ps = |model: &str| {
+        MockRoute::get("/api/ps").respond(running_models(model).delay(Duration::from_millis(50)))
+    };
+    let a = host("a").with_route(slow_ps("llama3"));
+    let b = host("b").with_route(slow_ps("qwen3"));
+    let pool = PoolTransport::new(PoolStrategy::ModelAffinity)
+        .with_host("a", Arc::new(a.clone()))
+        .with_host("b", Arc::new(b.clone()));
+    let client = client(pool)?;
+
+    let answers = futures::future::join_all((0..8).map(|_| answer(&client, "qwen3"))).await;
+    assert!(answers.iter().all(|answer| answer.is_ok()));
+    assert_eq!(a.call_count("/api/ps"), 1);
+    assert_eq!(b.call_count("/api/ps"), 1);
+
+    // Once the running models are known, requests go to the host with the model.
+    assert_eq!(answer(&client, "qwen3").await?, "b");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failing_host_is_ejected_and_readmitted_after_cooldown() -> Result<()> {
+    let failing = MockTransport::new()
+        .with_route(
+            MockRoute::post("/api/chat")
+                .respond(MockResponse::error(|| {
+                    Error::Transport("connection refused".into())
+                }))
+                .respond(MockResponse::error(|| {
+                    Error::Transport("connection refused".into())
+                })),
+        )
+        .with_route(chat_route("a", 10));
+    let pool = Arc::new(
+        PoolTransport::new(PoolStrategy::RoundRobin)
+            .with_host("a", Arc::new(failing.clone()))
+            .with_host("b", Arc::new(host("b")))
+            .failure_threshold(2)
+            .cooldown(Duration::from_millis(50)),
+    );
+    let client = OllamaClient::builder().transport(pool.clone()).build()?;
+
+    assert!(client.chat_simple(request("m")).await.is_err());
+    assert_eq!(answer(&client, "m").await?, "b");
+    assert!(client.chat_simple(request("m")).await.is_err());
+    assert!(pool.status()[0].ejected);
+
+    for _ in 0..3 {
+        assert_eq!(answer(&client, "m").await?, "b");
+    }
+    assert_eq!(failing.call_count("/api/chat"), 2);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let mut answers = Vec::new();
+    for _ in 0..2 {
+        answers.push(answer(&client, "m").await?);
+    }
+    answers.sort();
+    assert_eq!(answers, vec!["a", "b"]);
+    assert!(!pool.status()[0].ejected);
+    assert_eq!(pool.status()[0].consecutive_failures, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_error_statuses_do_not_eject_host() -> Result<()> {
+    let not_found = (0..3).fold(MockRoute::post("/api/chat"), |route, _| {
+        route.respond(
+            MockResponse::json(json!({ "error": "model 'm' not found" }))
+                .status(StatusCode::NOT_FOUND),
+        )
+    });
+    let pool = Arc::new(
+        PoolTransport::new(PoolStrategy::RoundRobin)
+            .with_host("a", Arc::new(MockTransport::new().with_route(not_found)))
+            .failure_threshold(1),
+    );
+    let client = OllamaClient::builder().transport(pool.clone()).build()?;
+
+    for _ in 0..3 {
+        assert!(matches!(
+            client.chat_simple(request("m")).await,
+            Err(Error::Http { status, .. }) if status == StatusCode::NOT_FOUND
+        ));
+    }
+    assert!(!pool.status()[0].ejected);
+    assert_eq!(pool.status()[0].consecutive_failures, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_error_statuses_eject_host() -> Result<()> {
+    let failing = MockRoute::post("/api/chat").respond(
+        MockResponse::json(json!({ "error": "out of memory" }))
+            .status(StatusCode::SERVICE_UNAVAILABLE),
+    );
+    let pool = Arc::new(
+        PoolTransport::new(PoolStrategy::RoundRobin)
+            .with_host("a", Arc::new(MockTransport::new().with_route(failing)))
+            .with_host("b", Arc::new(host("b")))
+            .failure_threshold(1),
+    );
+    let client = OllamaClient::builder().transport(pool.clone()).build()?;
+
+    assert!(client.chat_simple(request("m")).await.is_err());
+    assert!(pool.status()[0].ejected);
+    assert_eq!(answer(&client, "m").await?, "b");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_errors_count_as_host_failures() -> Result<()> {
+    let a = MockTransport::new().with_route(MockRoute::post("/api/chat").streaming(true).respond(
+        MockResponse::chunks(Vec::<String>::new()).then_error(|| Error::Transport("reset".into())),
+    ));
+    let pool = PoolTransport::new(PoolStrategy::RoundRobin)
+        .with_host("a", Arc::new(a))
+        .failure_threshold(1);
+
+    let mut response = pool
+        .send_http_stream_request(HttpRequest::new("/api/chat").post())
+        .await?;
+    assert!(matches!(
+        response.body.next().await,
+        Some(Err(Error::Transport(_)))
+    ));
+    assert!(pool.status()[0].ejected);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "reqwest")]
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use ollama_sdk::transport::{ReqwestTransport, Transport};
+use ollama_sdk::types::HttpRequest;
+use ollama_sdk::{Error, Result};
+
+// --- Helper for serving a canned HTTP response over TCP ---
+
+/// Accepts a single connection, reads the request head, and replies with `response`.
+/// Returns the base URL of the server and the raw request that was received.
+async fn serve_once(response: String) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        let mut buf = [0u8; 4096];
+        while !String::from_utf8_lossy(&received).contains("\r\n\r\n") {
+            let n = socket.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            received.extend_from_slice(&buf[..n]);
+        }
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+        String::from_utf8_lossy(&received).to_string()
+    });
+    (base_url, server)
+}
+
+fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn transport(base_url: &str) -> Result<ReqwestTransport> {
+    ReqwestTransport::new(reqwest::Url::parse(base_url).unwrap(), None)
+}
+
+#[tokio::test]
+async fn test_reqwest_error_status() -> Result<()> {
+    let (base_url, server) = serve_once(json_response(
+        "404 Not Found",
+        r#"{"error":"model 'missing' not found"}"#,
+    ))
+    .await;
+
+    let error = transport(&base_url)?
+        .send_http_stream_request(HttpRequest::new("/api/chat").post())
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            &error,
+            Error::Http { status, message }
+                if *status == 404 && message == "model 'missing' not found"
+        ),
+        "unexpected error: {}",
+        error
+    );
+    server.await.unwrap();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reqwest_keeps_base_url_path() -> Result<()> {
+    let (base_url, server) = serve_once(json_response("200 OK", r#"{"models":[]}"#)).await;
+
+    let response = transport(&format!("{}/ollama/", base_url))?
+        .send_http_request(HttpRequest::new("/api/tags"))
+        .await?;
+    assert_eq!(response.status, 200);
+    assert!(server
+        .await
+        .unwrap()
+        .starts_with("GET /ollama/api/tags HTTP/1.1"));
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde_json::json;
+
+use ollama_sdk::streaming::{StopConditions, StopReason, StreamChunk};
+use ollama_sdk::types::chat::ChatStream;
+use ollama_sdk::types::generate::GenerateStream;
+use ollama_sdk::{Error, Result};
+
+fn chat_line(content: &str, done: bool) -> Bytes {
+    let line = json!({ "model": "m", "message": { "role": "assistant", "content": content }, "done": done });
+    Bytes::from(format!("{}\n", line))
+}
+
+fn generate_line(response: &str, done: bool) -> Bytes {
+    let line = json!({ "model": "m", "created_at": "t", "response": response, "done": done });
+    Bytes::from(format!("{}\n", line))
+}
+
+#[tokio::test]
+async fn test_stop_sequence_split_across_chunks_drops_stream() -> Result<()> {
+    let guard = Arc::new(());
+    let body = {
+        let guard = guard.clone();
+        stream::iter(
+            [
+                "The answer",
+                " is 4",
+                "2.\n",
+                "\nQuest",
+                "ion: next",
+                " one",
+            ]
+            .map(|content| Ok(chat_line(content, false))),
+        )
+        // The server would keep generating; the adapter must not wait for it.
+        .chain(stream::pending())
+        .map(move |chunk| {
+            let _ = &guard;
+            chunk
+        })
+    };
+    let mut stream = ChatStream::from_bytes_stream(Box::pin(body))
+        .stop_on(StopConditions::new().sequences(["\n\nQuestion:", "never"]));
+
+    let mut contents = Vec::new();
+    while let Some(event) = stream.next().await {
+        contents.push(event?.content().unwrap().to_string());
+    }
+
+    assert_eq!(contents, vec!["The answer", " is 4", "2.", "", ""]);
+    assert_eq!(
+        stream.stop_reason(),
+        Some(&StopReason::Sequence("\n\nQuestion:".to_string()))
+    );
+    assert_eq!(Arc::strong_count(&guard), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_held_back_prefix_is_released_when_no_match() -> Result<()> {
+    let body = stream::iter(vec![
+        Ok(chat_line("abc ST", false)),
+        Ok(chat_line("ART de", false)),
+        Ok(chat_line("f ST", false)),
+        Ok(chat_line("", true)),
+    ]);
+    let mut stream =
+        ChatStream::from_bytes_stream(body).stop_on(StopConditions::new().sequence("STOP"));
+
+    let mut contents = Vec::new();
+    while let Some(event) = stream.next().await {
+        contents.push(event?.content().unwrap().to_string());
+    }
+
+    assert_eq!(contents, vec!["abc ", "START de", "f ", "ST"]);
+    assert_eq!(stream.stop_reason(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_chars_truncates_at_character_boundary() -> Result<()> {
+    let body = stream::iter(vec![
+        Ok(generate_line("héllo", false)),
+        Ok(generate_line(" wörld", false)),
+        Ok(generate_line("!", true)),
+    ]);
+    let mut stream = GenerateStream::from_bytes_stream(body)
+        .stop_on(StopConditions::new().sequence("ö").max_chars(6));
+
+    let mut contents = Vec::new();
+    while let Some(event) = stream.next().await {
+        contents.push(event?.content().unwrap().to_string());
+    }
+
+    assert_eq!(contents, vec!["héllo", " "]);
+    assert_eq!(stream.stop_reason(), Some(&StopReason::MaxLength(6)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_earliest_stop_sequence_wins() -> Result<()> {
+    let body = stream::iter(vec![Ok(generate_line("one. two! three.", true))]);
+    let mut stream = GenerateStream::from_bytes_stream(body).stop_on(
+        StopConditions::new()
+            .sequences([".", "!", ""])
+            .max_chars(100),
+    );
+
+    let event = stream.next().await.unwrap()?;
+    assert_eq!(event.content(), Some("one"));
+    assert!(stream.next().await.is_none());
+    assert_eq!(
+        stream.stop_reason().map(ToString::to_string).as_deref(),
+        Some("stop sequence \".\"")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_held_back_prefix_is_flushed_when_stream_ends_or_fails() -> Result<()> {
+    // The stream ends without a final chunk while holding a stop sequence prefix.
+    let body = stream::iter([Ok(chat_line("Done.\n", false))]);
+    let mut stream =
+        ChatStream::from_bytes_stream(body).stop_on(StopConditions::new().sequence("\n\nUser:"));
+    let mut contents = Vec::new();
+    while let Some(event) = stream.next().await {
+        contents.push(event?.content().unwrap().to_string());
+    }
+    assert_eq!(contents, vec!["Done.", "\n"]);
+    assert_eq!(stream.stop_reason(), None);
+
+    // The held prefix comes before the error that interrupted the stream.
+    let body = stream::iter([
+        Ok(generate_line("Total: 4", false)),
+        Err(Error::Transport("connection reset".into())),
+    ]);
+    let mut stream =
+        GenerateStream::from_bytes_stream(body).stop_on(StopConditions::new().sequence("42"));
+    assert_eq!(stream.next().await.unwrap()?.content(), Some("Total: "));
+    assert_eq!(stream.next().await.unwrap()?.content(), Some("4"));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::Transport(_)))
+    ));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use ollama_sdk::parser::{
+    Framing, ParserConfig, StreamEvent, StreamEventExt, StreamLineError, StreamLineErrorKind,
+};
+use ollama_sdk::{Error, Result};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct PullStatus {
+    pub status: String,
+}
+
+#[derive(Debug, PartialEq, StreamEvent)]
+enum PullEvent {
+    #[stream(message)]
+    Status(PullStatus),
+    #[stream(error)]
+    Failed(String),
+    #[stream(partial)]
+    Garbled(String, Option<String>),
+}
+
+#[derive(Debug, StreamEvent)]
+pub enum ProgressStreamEvent {
+    #[stream(message)]
+    Progress(PullStatus),
+    #[stream(error)]
+    Error(String),
+    #[stream(partial)]
+    Partial {
+        error: Option<String>,
+        partial: String,
+    },
+    #[stream(line_error)]
+    Line(StreamLineError),
+}
+
+fn body(chunks: &[&str]) -> impl futures::Stream<Item = Result<Bytes>> + Unpin {
+    let chunks: Vec<_> = chunks
+        .iter()
+        .map(|chunk| Ok(Bytes::from(chunk.to_string())))
+        .collect();
+    stream::iter(chunks)
+}
+
+#[test]
+fn test_derived_conversions() {
+    assert_eq!(
+        PullEvent::from_error("boom".to_string()),
+        PullEvent::Failed("boom".to_string())
+    );
+    assert_eq!(
+        PullEvent::partial("{".to_string(), None),
+        PullEvent::Garbled("{".to_string(), None)
+    );
+    assert!(matches!(
+        ProgressStreamEvent::partial("{".to_string(), Some("eof".to_string())),
+        ProgressStreamEvent::Partial { partial, error: Some(error) } if partial == "{" && error == "eof"
+    ));
+}
+
+#[tokio::test]
+async fn test_derived_stream_parses_body() -> Result<()> {
+    let events: Vec<_> = PullStream::from_bytes_stream(body(&[
+        "{\"status\": \"pulling\"}\n{\"err",
+        "or\": \"disk full\"}\n{\"status\": 1}\n",
+    ]))
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .collect::<Result<_>>()?;
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(
+        events[0],
+        PullEvent::Status(PullStatus {
+            status: "pulling".to_string()
+        })
+    );
+    assert_eq!(events[1], PullEvent::Failed("disk full".to_string()));
+    assert!(matches!(
+        &events[2],
+        PullEvent::Garbled(partial, Some(_)) if partial == "{\"status\": 1}"
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_derived_stream_with_config_and_line_errors() {
+    let mut stream = ProgressStream::from_bytes_stream_with_config(
+        body(&[
+            "data: {\"status\": \"a\"}\n\n",
+            "event: error\ndata: {\"error\": \"slow\"}\n\n",
+            "data: {\"status\": [\n\n",
+        ]),
+        ParserConfig::new().framing(Framing::Sse).strict(true),
+    );
+
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(ProgressStreamEvent::Progress(status))) if status.status == "a"
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(ProgressStreamEvent::Line(error)))
+            if error.is_server_error() && error.message() == "slow" && error.line() == 3
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::MalformedLine(error)))
+            if matches!(error.kind(), StreamLineErrorKind::Malformed(_)) && error.line() == 5
+    ));
+    assert!(stream.next().await.is_none());
+}
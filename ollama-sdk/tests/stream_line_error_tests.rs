@@ -0,0 +1,320 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
+use ollama_sdk::parser::{
+    Framing, GenericStreamParser, ParserConfig, StreamEventExt, StreamLineError,
+    StreamLineErrorKind,
+};
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{ChatStream, ChatStreamEvent, StreamingChatRequest};
+use ollama_sdk::types::generate::{GenerateStream, GenerateStreamEvent};
+use ollama_sdk::{Error, OllamaClient, Result};
+use serde::Deserialize;
+use serde_json::error::Category;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Message {
+    id: u32,
+}
+
+/// An event that keeps the context of error lines.
+#[derive(Debug)]
+enum Event {
+    Message(Message),
+    Line(StreamLineError),
+}
+
+impl StreamEventExt<Message> for Event {
+    fn from_message(msg: Message) -> Self {
+        Event::Message(msg)
+    }
+
+    fn from_error(err: String) -> Self {
+        unreachable!("from_line_error is overridden: {}", err)
+    }
+
+    fn partial(partial: String, _error: Option<String>) -> Self {
+        unreachable!("from_line_error is overridden: {}", partial)
+    }
+
+    fn from_line_error(error: StreamLineError) -> Self {
+        Event::Line(error)
+    }
+}
+
+fn new_parser(
+    chunks: Vec<&str>,
+    config: ParserConfig,
+) -> GenericStreamParser<impl Stream<Item = Result<Bytes>> + Unpin, Message, Event> {
+    let chunks: Vec<_> = chunks
+        .into_iter()
+        .map(|chunk| Ok(Bytes::from(chunk.to_string())))
+        .collect();
+    GenericStreamParser::with_config(stream::iter(chunks), config)
+}
+
+async fn line_error(parser: &mut (impl Stream<Item = Result<Event>> + Unpin)) -> StreamLineError {
+    match parser.next().await {
+        Some(Ok(Event::Line(error))) => error,
+        other => panic!("expected a line error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_line_errors_carry_position_and_category() {
+    let mut parser = new_parser(
+        vec![
+            "{\"id\": 1}\n\n{\"err",
+            "or\": \"overloaded\"}\n{\"id\": \"two\"}\r\n  {id: 3}\n",
+            "{\"id\": 4}\n{\"id\"",
+        ],
+        ParserConfig::new(),
+    );
+
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(Event::Message(Message { id: 1 })))
+    ));
+
+    let server = line_error(&mut parser).await;
+    assert_eq!(server.kind(), StreamLineErrorKind::Server);
+    assert!(server.is_server_error());
+    assert_eq!(server.message(), "overloaded");
+    assert_eq!((server.line(), server.offset()), (2, 11));
+    assert_eq!(server.raw().as_ref(), br#"{"error": "overloaded"}"#);
+    assert!(server.can_continue());
+
+    let data = line_error(&mut parser).await;
+    assert_eq!(data.kind(), StreamLineErrorKind::Malformed(Category::Data));
+    assert!(!data.is_server_error());
+    assert_eq!((data.line(), data.offset()), (3, 35));
+    assert_eq!(data.raw().as_ref(), br#"{"id": "two"}"#);
+    assert!(data.message().contains("invalid type"));
+    assert!(data.can_continue());
+
+    let syntax = line_error(&mut parser).await;
+    assert_eq!(
+        syntax.kind(),
+        StreamLineErrorKind::Malformed(Category::Syntax)
+    );
+    assert_eq!((syntax.line(), syntax.offset()), (4, 50));
+    assert_eq!(syntax.raw().as_ref(), b"{id: 3}");
+
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(Event::Message(Message { id: 4 })))
+    ));
+
+    let truncated = line_error(&mut parser).await;
+    assert_eq!(truncated.kind(), StreamLineErrorKind::Truncated);
+    assert_eq!((truncated.line(), truncated.offset()), (6, 70));
+    assert_eq!(truncated.raw().as_ref(), br#"{"id""#);
+    assert!(!truncated.can_continue());
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_sse_line_errors_point_at_first_data_line() {
+    let mut parser = new_parser(
+        vec![
+            ": hi\n\nevent: error\ndata: {\"error\": \"boom\"}\n\n",
+            "id: 7\ndata: [\"x\",\ndata: 2]\n\n",
+        ],
+        ParserConfig::new().framing(Framing::Sse),
+    );
+
+    let server = line_error(&mut parser).await;
+    assert!(server.is_server_error());
+    assert_eq!(server.message(), "boom");
+    assert_eq!((server.line(), server.offset()), (3, 19));
+
+    let malformed = line_error(&mut parser).await;
+    assert_eq!(
+        malformed.kind(),
+        StreamLineErrorKind::Malformed(Category::Data)
+    );
+    assert_eq!((malformed.line(), malformed.offset()), (6, 50));
+    assert_eq!(malformed.raw().as_ref(), b"[\"x\",\n2]");
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_strict_mode_ends_stream_at_malformed_line() {
+    let mut parser = new_parser(
+        vec!["{\"error\": \"retrying\"}\n{\"id\": 1}\n{\"id\": -1}\n{\"id\": 2}\n"],
+        ParserConfig::new().strict(true),
+    );
+
+    let server = line_error(&mut parser).await;
+    assert!(server.is_server_error());
+    assert!(server.can_continue());
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(Event::Message(Message { id: 1 })))
+    ));
+
+    let error = match parser.next().await {
+        Some(Err(Error::MalformedLine(error))) => error,
+        other => panic!("expected a malformed line, got {:?}", other),
+    };
+    assert_eq!(error.kind(), StreamLineErrorKind::Malformed(Category::Data));
+    assert_eq!((error.line(), error.offset()), (2, 32));
+    assert!(!error.can_continue());
+    assert!(Error::MalformedLine(error)
+        .to_string()
+        .starts_with("Malformed stream line 2 at byte 32: "));
+    assert!(parser.next().await.is_none());
+
+    let mut parser = new_parser(
+        vec!["{\"id\": 1}\n{\"id\": 2"],
+        ParserConfig::new().strict(true),
+    );
+    assert!(matches!(
+        parser.next().await,
+        Some(Ok(Event::Message(Message { id: 1 })))
+    ));
+    assert!(matches!(
+        parser.next().await,
+        Some(Err(Error::MalformedLine(error))) if error.kind() == StreamLineErrorKind::Truncated
+    ));
+    assert!(parser.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_client_strict_parser_config() -> Result<()> {
+    let line = r#"{"model":"m","message":{"role":"assistant","content":"hi"},"done":false}"#;
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat")
+            .streaming(true)
+            .respond(MockResponse::ndjson(vec![line, "not json", line])),
+    );
+    let client = OllamaClient::builder()
+        .transport(Arc::new(mock))
+        .parser_config(ParserConfig::new().strict(true))
+        .build()?;
+
+    let events: Vec<_> = client
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], Ok(ChatStreamEvent::Message(_))));
+    assert!(matches!(
+        &events[1],
+        Err(Error::MalformedLine(error)) if error.line() == 1 && error.raw().as_ref() == b"not json"
+    ));
+    Ok(())
+}
+
+fn body(chunks: Vec<String>) -> impl Stream<Item = Result<Bytes>> + Send + Unpin + 'static {
+    stream::iter(chunks.into_iter().map(|chunk| Ok(Bytes::from(chunk))))
+}
+
+#[tokio::test]
+async fn test_chat_stream_reports_line_errors_with_position() -> Result<()> {
+    let message = r#"{"model":"m","message":{"role":"assistant","content":"hi"},"done":false}"#;
+    let chunks = vec![
+        format!("{message}\n{{\"error\": \"overloaded\"}}\n{{\"mod"),
+        "el\": 1}\n".to_string(),
+        format!("{message}\n{{\"model\""),
+    ];
+    let events: Vec<_> = ChatStream::from_bytes_stream(body(chunks))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_>>()?;
+    assert_eq!(events.len(), 5);
+
+    let offset = message.len() as u64 + 1;
+    let ChatStreamEvent::LineError(server) = &events[1] else {
+        panic!("expected a line error, got {:?}", events[1]);
+    };
+    assert!(server.is_server_error());
+    assert_eq!(server.message(), "overloaded");
+    assert_eq!((server.line(), server.offset()), (1, offset));
+    assert!(server.can_continue());
+
+    let ChatStreamEvent::LineError(data) = &events[2] else {
+        panic!("expected a line error, got {:?}", events[2]);
+    };
+    assert_eq!(data.kind(), StreamLineErrorKind::Malformed(Category::Data));
+    assert_eq!((data.line(), data.offset()), (2, offset + 24));
+    assert_eq!(data.raw().as_ref(), br#"{"model": 1}"#);
+    assert!(data.can_continue());
+
+    assert!(matches!(events[3], ChatStreamEvent::Message(_)));
+    let ChatStreamEvent::LineError(truncated) = &events[4] else {
+        panic!("expected a line error, got {:?}", events[4]);
+    };
+    assert_eq!(truncated.kind(), StreamLineErrorKind::Truncated);
+    assert_eq!(
+        (truncated.line(), truncated.offset()),
+        (4, 2 * offset + 24 + 13)
+    );
+    assert!(!truncated.can_continue());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_stream_reports_line_errors_with_position() -> Result<()> {
+    let message = r#"{"model":"m","created_at":"t","response":"Once","done":false}"#;
+    let chunk = format!("{message}\n\n{{\"error\": \"model crashed\"}}\nnot json\n");
+    let events: Vec<_> = GenerateStream::from_bytes_stream(body(vec![chunk]))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_>>()?;
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[0], GenerateStreamEvent::MessageChunk(_)));
+
+    let offset = message.len() as u64 + 2;
+    let GenerateStreamEvent::LineError(server) = &events[1] else {
+        panic!("expected a line error, got {:?}", events[1]);
+    };
+    assert!(server.is_server_error());
+    assert_eq!(server.message(), "model crashed");
+    assert_eq!((server.line(), server.offset()), (2, offset));
+
+    let GenerateStreamEvent::LineError(syntax) = &events[2] else {
+        panic!("expected a line error, got {:?}", events[2]);
+    };
+    assert_eq!(
+        syntax.kind(),
+        StreamLineErrorKind::Malformed(Category::Syntax)
+    );
+    assert_eq!((syntax.line(), syntax.offset()), (3, offset + 27));
+    assert_eq!(syntax.raw().as_ref(), b"not json");
+    assert!(syntax.can_continue());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_line_error_events_round_trip_through_serde() -> Result<()> {
+    let events: Vec<_> =
+        ChatStream::from_bytes_stream(body(vec!["{\"id\": \"two\"}\n{\"id\"".to_string()]))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+    let ChatStreamEvent::LineError(data) = &events[0] else {
+        panic!("expected a line error, got {:?}", events[0]);
+    };
+
+    let value = serde_json::to_value(&events[0])?;
+    assert_eq!(value["kind"], "line_error");
+    assert_eq!(value["payload"]["kind"], "data");
+    assert_eq!(value["payload"]["raw"], "{\"id\": \"two\"}");
+    let ChatStreamEvent::LineError(decoded) = serde_json::from_value(value)? else {
+        panic!("expected a line error");
+    };
+    assert_eq!(&decoded, data);
+
+    let value = serde_json::to_value(&events[1])?;
+    assert_eq!(value["payload"]["kind"], "truncated");
+    assert_eq!(value["payload"]["line"], 1);
+    assert_eq!(value["payload"]["offset"], 14);
+    assert_eq!(value["payload"]["can_continue"], false);
+    Ok(())
+}
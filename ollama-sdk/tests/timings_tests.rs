@@ -0,0 +1,165 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde_json::json;
+
+use ollama_sdk::transport::{MockResponse, MockRoute, MockTransport};
+use ollama_sdk::types::chat::{ChatStream, StreamingChatRequest};
+use ollama_sdk::types::generate::{GenerateStreamEvent, StreamingGenerateRequest};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+const DELAY: Duration = Duration::from_millis(30);
+const CHUNK_DELAY: Duration = Duration::from_millis(20);
+
+fn client(mock: MockTransport) -> Result<OllamaClient> {
+    OllamaClient::builder().transport(Arc::new(mock)).build()
+}
+
+#[tokio::test]
+async fn test_chat_stream_timings() -> Result<()> {
+    let lines = [
+        json!({ "model": "m", "message": { "role": "assistant", "content": "", "thinking": "Hmm" }, "done": false }),
+        json!({ "model": "m", "message": { "role": "assistant", "content": "Hel" }, "done": false }),
+        json!({ "model": "m", "message": { "role": "assistant", "content": "lo" }, "done": false }),
+        json!({ "model": "m", "message": { "role": "assistant", "content": "" }, "done": true }),
+    ];
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/chat").streaming(true).respond(
+            MockResponse::ndjson(lines.iter().map(|line| line.to_string()))
+                .delay(DELAY)
+                .chunk_delay(CHUNK_DELAY),
+        ),
+    );
+    let mut stream = client(mock)?
+        .chat_stream(StreamingChatRequest::new("m".to_string()))
+        .await?;
+
+    let timings = stream.timings().unwrap();
+    assert!(timings.first_byte().is_none());
+    assert!(timings.completed().is_none());
+
+    while let Some(event) = stream.next().await {
+        event?;
+    }
+    let timings = stream.timings().unwrap();
+    let first_byte = timings.time_to_first_byte().unwrap();
+    let first_token = timings.time_to_first_token().unwrap();
+    let total = timings.total_duration().unwrap();
+    assert!(first_byte >= DELAY + CHUNK_DELAY, "{:?}", first_byte);
+    assert!(first_token >= first_byte);
+    assert!(total >= first_token + 3 * CHUNK_DELAY, "{:?}", total);
+    assert_eq!(timings.token_chunks(), 3);
+    assert!(timings.mean_inter_token_latency().unwrap() >= CHUNK_DELAY);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_stream_timings_complete_on_error() -> Result<()> {
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/generate").streaming(true).respond(
+            MockResponse::ndjson([
+                json!({ "model": "m", "created_at": "t0", "response": "Once", "done": false })
+                    .to_string(),
+            ])
+            .then_error(|| Error::Transport("connection reset".into())),
+        ),
+    );
+    let mut stream = client(mock)?
+        .generate_stream(StreamingGenerateRequest::new(
+            "m".to_string(),
+            "Tell a story".to_string(),
+        ))
+        .await?;
+
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(GenerateStreamEvent::MessageChunk(_)))
+    ));
+    let timings = stream.timings().unwrap();
+    assert_eq!(timings.token_chunks(), 1);
+    assert!(timings.mean_inter_token_latency().is_none());
+    assert!(timings.completed().is_none());
+
+    assert!(stream.next().await.unwrap().is_err());
+    let timings = stream.timings().unwrap();
+    assert!(timings.completed().is_some());
+    assert!(timings.first_token().unwrap() <= timings.completed().unwrap());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streams_from_bytes_are_not_timed() {
+    let stream = ChatStream::from_bytes_stream(stream::iter(vec![Ok(Bytes::from_static(
+        b"{\"model\":\"m\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n",
+    ))]));
+    assert!(stream.timings().is_none());
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_stream_metrics_record_gaps_and_dropped_streams_but_not_failed_requests() -> Result<()> {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    let lines = ["Once", " upon", " a", " time"].map(|token| {
+        json!({ "model": "m", "created_at": "t", "response": token, "done": false }).to_string()
+    });
+    let mock = MockTransport::new().with_route(
+        MockRoute::post("/api/generate")
+            .streaming(true)
+            .respond(
+                MockResponse::json(json!({ "error": "overloaded" }))
+                    .status(http::StatusCode::SERVICE_UNAVAILABLE),
+            )
+            .respond(MockResponse::ndjson(lines).chunk_delay(CHUNK_DELAY)),
+    );
+    let client = client(mock)?;
+    let request = || StreamingGenerateRequest::new("m".to_string(), "Tell a story".to_string());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()?;
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        runtime.block_on(async {
+            // A request that fails before its stream starts records nothing.
+            assert!(matches!(
+                client.generate_stream(request()).await,
+                Err(Error::Http { .. })
+            ));
+
+            let mut stream = client.generate_stream(request()).await?;
+            for _ in 0..3 {
+                stream.next().await.unwrap()?;
+            }
+            // The stream is dropped without its final chunk, as after a stop sequence.
+            drop(stream);
+            Ok::<_, Error>(())
+        })
+    })?;
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let samples = |name: &str| -> Vec<f64> {
+        snapshot
+            .iter()
+            .filter(|(key, ..)| key.key().name() == name)
+            .flat_map(|(.., value)| match value {
+                DebugValue::Histogram(values) => values.iter().map(|v| v.0).collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    };
+    let gaps = samples("ollama_client.stream_inter_token_latency_seconds");
+    assert_eq!(gaps.len(), 2);
+    assert!(gaps
+        .iter()
+        .all(|gap| *gap >= CHUNK_DELAY.as_secs_f64() * 0.9));
+    assert_eq!(
+        samples("ollama_client.stream_time_to_first_token_seconds").len(),
+        1
+    );
+    assert_eq!(samples("ollama_client.stream_duration_seconds").len(), 1);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+#![cfg(all(unix, feature = "unix-socket"))]
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+
+use ollama_sdk::transport::{Transport, UnixSocketTransport};
+use ollama_sdk::types::chat::{
+    ChatStreamEvent, RegularChatRequestMessage, SimpleChatRequest, StreamingChatRequest,
+};
+use ollama_sdk::types::{HttpRequest, Role};
+use ollama_sdk::{Error, OllamaClient, Result};
+
+// --- Helpers for serving a canned HTTP response over a unix socket ---
+
+fn socket_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("ollama-sdk-{}-{}.sock", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Accepts a single connection, reads the request head and body, and replies with `response`.
+/// Returns the raw request that was received.
+fn serve_once(path: &PathBuf, response: String) -> tokio::task::JoinHandle<String> {
+    let listener = UnixListener::bind(path).unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            received.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&received).to_string();
+            if let Some(head_end) = text.find("\r\n\r\n") {
+                let content_length = text[..head_end]
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if received.len() >= head_end + 4 + content_length {
+                    break;
+                }
+            }
+            if n == 0 {
+                break;
+            }
+        }
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+        String::from_utf8_lossy(&received).to_string()
+    })
+}
+
+fn chunked_response(lines: &[&str]) -> String {
+    let mut response = String::from(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ntransfer-encoding: chunked\r\n\r\n",
+    );
+    for line in lines {
+        let chunk = format!("{}\n", line);
+        response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+    }
+    response.push_str("0\r\n\r\n");
+    response
+}
+
+#[tokio::test]
+async fn test_unix_socket_chat_simple() -> Result<()> {
+    let path = socket_path("chat-simple");
+    let body = r#"{"model":"test-model","message":{"role":"assistant","content":"Hello over a socket"},"done":true}"#;
+    let server = serve_once(
+        &path,
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+    );
+
+    let client = OllamaClient::builder()
+        .unix_socket(&path)
+        .api_key("secret")
+        .build()?;
+    let request = SimpleChatRequest::new("test-model".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()));
+
+    let response = client.chat_simple(request).await?;
+    assert_eq!(response.message.content, "Hello over a socket");
+
+    let raw_request = server.await.unwrap();
+    assert!(raw_request.starts_with("POST /api/chat HTTP/1.1"));
+    assert!(raw_request.contains("authorization: Bearer secret"));
+    assert!(raw_request.contains(r#""model":"test-model""#));
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unix_socket_chat_stream() -> Result<()> {
+    let path = socket_path("chat-stream");
+    let server = serve_once(
+        &path,
+        chunked_response(&[
+            r#"{"model":"test-model","message":{"role":"assistant","content":"Hello"},"done":false}"#,
+            r#"{"model":"test-model","message":{"role":"assistant","content":" world"},"done":true}"#,
+        ]),
+    );
+
+    let client = OllamaClient::builder()
+        .base_url(format!("unix://{}", path.display()))
+        .build()?;
+    let request = StreamingChatRequest::new("test-model".to_string()).add_regular_message(
+        RegularChatRequestMessage::new(Role::User, "Stream me".to_string()),
+    );
+
+    let mut stream = client.chat_stream(request).await?;
+    let mut received_content = String::new();
+    while let Some(event) = stream.next().await {
+        if let ChatStreamEvent::Message(response) = event? {
+            received_content.push_str(&response.message.content);
+        }
+    }
+    assert_eq!(received_content, "Hello world");
+
+    server.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unix_socket_error_status() -> Result<()> {
+    let path = socket_path("error-status");
+    let body = r#"{"error":"model 'missing' not found"}"#;
+    let server = serve_once(
+        &path,
+        format!(
+            "HTTP/1.1 404 Not Found\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+    );
+
+    let client = OllamaClient::builder().unix_socket(&path).build()?;
+    let request = SimpleChatRequest::new("missing".to_string())
+        .add_message(RegularChatRequestMessage::new(Role::User, "Hi".to_string()));
+
+    let error = client.chat_simple(request).await.unwrap_err();
+    assert!(
+        matches!(
+            &error,
+            Error::Http { status, message }
+                if *status == 404 && message == "model 'missing' not found"
+        ),
+        "unexpected error: {}",
+        error
+    );
+
+    server.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unix_socket_missing_socket() -> Result<()> {
+    let path = socket_path("missing");
+    let client = OllamaClient::builder().unix_socket(&path).build()?;
+
+    let error = client.list_models().await.unwrap_err();
+    assert!(matches!(error, Error::Transport(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unix_socket_response_metadata_and_request_headers() -> Result<()> {
+    let path = socket_path("metadata");
+    let body = r#"{"models":[]}"#;
+    let server = serve_once(
+        &path,
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nx-request-id: abc123\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+    );
+
+    let transport = UnixSocketTransport::new(&path, None);
+    let request = HttpRequest::new("/api/tags").header("x-trace-id", "trace-1")?;
+    let response = transport.send_http_request(request).await?;
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.content_type(), Some("application/json"));
+    assert_eq!(response.headers.get("x-request-id").unwrap(), "abc123");
+    assert!(response.timing.total.unwrap() >= response.timing.time_to_headers);
+
+    let raw_request = server.await.unwrap();
+    assert!(raw_request.contains("x-trace-id: trace-1"));
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unix_socket_request_timeout() -> Result<()> {
+    let path = socket_path("timeout");
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    // Accept the connection but never answer.
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(socket);
+    });
+
+    let transport = UnixSocketTransport::new(&path, None);
+    let request = HttpRequest::new("/api/tags").timeout(Duration::from_millis(50));
+    let error = transport.send_http_request(request).await.unwrap_err();
+    assert!(matches!(error, Error::Transport(_)));
+
+    server.abort();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}